@@ -0,0 +1,90 @@
+//! Where the hardhat config is read from. By default that's
+//! `hardhat.config.ts` in the project root, but the CLI can point every reader
+//! at another file or at config content piped through stdin.
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Name of the config file looked up in the project root
+pub const DEFAULT_CONFIG_FILE: &str = "hardhat.config.ts";
+
+/// Where a project's config comes from.
+#[derive(Debug, Clone, Default)]
+pub enum Source {
+    /// `hardhat.config.ts` in the project root
+    #[default]
+    ProjectRoot,
+    /// A config file somewhere else
+    File(PathBuf),
+    /// Config content already in memory, e.g. read from stdin or an archived
+    /// snapshot. `name` stands in for the file name in messages and SARIF
+    /// output.
+    Content { name: String, content: String },
+}
+
+impl Source {
+    /// Reads the config from stdin, right away so every reader sees the same
+    /// content.
+    pub fn stdin(name: &str) -> Result<Source, String> {
+        let mut content = String::new();
+        std::io::stdin().read_to_string(&mut content)
+            .map_err(|e| format!("Failed to read the config from stdin: {}", e))?;
+        Ok(Source::Content { name: name.to_string(), content })
+    }
+
+    /// Path of the config file for the project at `root`, or `None` when the
+    /// config is in memory.
+    pub fn path(&self, root: &Path) -> Option<PathBuf> {
+        match self {
+            Source::ProjectRoot => Some(root.join(DEFAULT_CONFIG_FILE)),
+            Source::File(path) => Some(path.clone()),
+            Source::Content { .. } => None,
+        }
+    }
+
+    /// File name of the config, as shown in messages.
+    pub fn name(&self, root: &Path) -> String {
+        match self {
+            Source::Content { name, .. } => name.clone(),
+            _ => self.path(root)
+                .and_then(|path| path.file_name().map(|name| name.to_string_lossy().into_owned()))
+                .unwrap_or_else(|| DEFAULT_CONFIG_FILE.to_string()),
+        }
+    }
+
+    /// Whether there is a config to read.
+    pub fn exists(&self, root: &Path) -> bool {
+        self.path(root).is_none_or(|path| path.is_file())
+    }
+
+    /// Reads the config content for the project at `root`.
+    pub fn read(&self, root: &Path) -> Result<String, String> {
+        match self {
+            Source::Content { content, .. } => Ok(content.clone()),
+            _ => {
+                let path = self.path(root).expect("file sources have a path");
+                fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", self.name(root), e))
+            }
+        }
+    }
+}
+
+/// Splits the `--project` path into the project root and, when it points at a
+/// hardhat config file (a common mistake), that file. Any other file is an
+/// error, since joining config and deployment paths onto it makes no sense.
+pub fn project_root(project: &Path) -> Result<(PathBuf, Option<PathBuf>), String> {
+    if !project.is_file() {
+        return Ok((project.to_path_buf(), None));
+    }
+    let is_config = project.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with("hardhat.config."));
+    if !is_config {
+        return Err(format!("--project {} is a file, expected the hardhat project directory", project.display()));
+    }
+    let root = match project.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    Ok((root, Some(project.to_path_buf())))
+}
@@ -0,0 +1,167 @@
+//! Everything the analysis needs to know about a project: where its files
+//! and config are read from, which directories inside `deployments` to look
+//! at, how chains are named, how errors reading a single network are
+//! handled and where the time goes. Nothing here is global, so one process
+//! can analyse several projects, or one project with several configs, side
+//! by side.
+
+use crate::config;
+use crate::errors::{ErrorLog, ErrorMode};
+use crate::registry;
+use crate::source::{DeploymentSource, WorkingTree};
+use crate::timing::Timings;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+/// A project to analyse and the settings to analyse it with.
+pub struct Context {
+    /// The project root, holding the hardhat config and `deployments`. For
+    /// a project read from an archive, the paths under it only name files in
+    /// the archive.
+    pub root: PathBuf,
+    /// Where the project files are read from, the working tree by default
+    pub source: Box<dyn DeploymentSource>,
+    pub config: config::Source,
+    /// Directories inside `deployments` to skip, besides hidden ones
+    pub ignore_dirs: Vec<String>,
+    /// When set, only directories inside `deployments` matching these globs
+    /// are looked at
+    pub chain_globs: Option<GlobSet>,
+    /// Chain names taking precedence over the bundled registry
+    pub network_names: HashMap<u64, String>,
+    pub errors: ErrorLog,
+    pub timings: Timings,
+}
+
+impl Context {
+    /// A context for the project at `root`, reading `hardhat.config.ts` from
+    /// the root and looking at every deployment.
+    pub fn new(root: impl Into<PathBuf>) -> Context {
+        let root = root.into();
+        Context {
+            source: Box::new(WorkingTree { root: root.clone() }),
+            root,
+            config: config::Source::default(),
+            ignore_dirs: Vec::new(),
+            chain_globs: None,
+            network_names: HashMap::new(),
+            errors: ErrorLog::new(ErrorMode::default()),
+            timings: Timings::default(),
+        }
+    }
+
+    /// Restricts the analysis to the directories inside `deployments` whose
+    /// name matches one of `patterns` (e.g. `chain-1*`), independent of the
+    /// config.
+    pub fn set_chain_globs(&mut self, patterns: &[String]) -> Result<(), String> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(Glob::new(pattern).map_err(|e| format!("Invalid --chain-glob pattern {}: {}", pattern, e))?);
+        }
+        self.chain_globs = Some(builder.build().map_err(|e| format!("Invalid --chain-glob patterns: {}", e))?);
+        Ok(())
+    }
+
+    /// Whether the directory inside `deployments` named `name` matches the
+    /// chain globs, if any were set.
+    pub fn is_selected_chain_dir(&self, name: &str) -> bool {
+        self.chain_globs.as_ref().is_none_or(|globs| globs.is_match(name))
+    }
+
+    /// Whether a directory inside `deployments` should be skipped, either
+    /// because it's hidden, because it's one of the ignored directories, or
+    /// because it doesn't match the chain globs.
+    pub fn is_ignored_dir(&self, path: &Path) -> bool {
+        path.file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|name| self.is_ignored_dir_name(name))
+    }
+
+    /// Like [`Context::is_ignored_dir`], for a directory name.
+    pub fn is_ignored_dir_name(&self, name: &str) -> bool {
+        name.starts_with('.') || self.ignore_dirs.iter().any(|ignored| ignored == name) || !self.is_selected_chain_dir(name)
+    }
+
+    /// Name of a chain, from the overrides or else the bundled registry.
+    pub fn chain_name(&self, chain_id: u64) -> Option<&str> {
+        self.network_names.get(&chain_id)
+            .map(String::as_str)
+            .or_else(|| registry::chain_name(chain_id))
+    }
+
+    /// Path of the config file, or `None` when the config is in memory.
+    pub fn config_path(&self) -> Option<PathBuf> {
+        self.config.path(&self.root)
+    }
+
+    /// File name of the config, as shown in messages.
+    pub fn config_name(&self) -> String {
+        self.config.name(&self.root)
+    }
+
+    /// Whether there is a config to read.
+    pub fn config_exists(&self) -> bool {
+        self.config.exists(&self.root)
+    }
+
+    /// Reads the config content.
+    pub fn read_config(&self) -> Result<String, String> {
+        self.config.read(&self.root)
+    }
+
+    /// Reads the project file at `path` (under the root) from the source, or
+    /// `None` if it doesn't exist.
+    pub fn read_file(&self, path: &Path) -> Result<Option<String>, String> {
+        self.source.read_file(&self.source_path(path))
+    }
+
+    /// Lists the directory at `path` (under the root) in the source, see
+    /// [`DeploymentSource::list_dir`].
+    pub fn list_dir(&self, path: &Path) -> Result<Option<Vec<(String, bool)>>, String> {
+        self.source.list_dir(&self.source_path(path))
+    }
+
+    /// Whether the file at `path` (under the root) exists in the source.
+    pub fn is_file(&self, path: &Path) -> bool {
+        let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+            return false;
+        };
+        self.list_dir(parent).ok().flatten()
+            .is_some_and(|entries| entries.iter().any(|(entry, is_dir)| !is_dir && *entry == *name.to_string_lossy()))
+    }
+
+    /// When the file at `path` (under the root) was last modified, if the
+    /// source knows.
+    pub fn modified(&self, path: &Path) -> Option<SystemTime> {
+        self.source.modified(&self.source_path(path))
+    }
+
+    /// Errors unless the project is read from disk, for what runs git or
+    /// other programs on it.
+    pub fn require_working_tree(&self, what: &str) -> Result<(), String> {
+        if self.source.is_working_tree() {
+            Ok(())
+        } else {
+            Err(format!("{} needs the project on disk, it can't read an archive", what))
+        }
+    }
+
+    /// `path` relative to the root with `/` separators, as the source
+    /// expects it. `..` is resolved, since artifacts point at their
+    /// build-info with relative paths.
+    fn source_path(&self, path: &Path) -> String {
+        let mut parts = Vec::new();
+        for component in path.strip_prefix(&self.root).unwrap_or(path).components() {
+            match component {
+                Component::ParentDir => {
+                    parts.pop();
+                }
+                Component::Normal(part) => parts.push(part.to_string_lossy()),
+                _ => {}
+            }
+        }
+        parts.join("/")
+    }
+}
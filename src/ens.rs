@@ -0,0 +1,59 @@
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::rpc::{decode_hex, encode_hex, RpcClient};
+
+/// ENS registry address on Ethereum mainnet
+const ENS_REGISTRY: &str = "0x00000000000C2E074eC69A0dFb2997BA6C7d2e1e";
+/// `resolver(bytes32)`
+const RESOLVER_SELECTOR: &str = "0178b8bf";
+/// `name(bytes32)`
+const NAME_SELECTOR: &str = "691f3431";
+
+pub(crate) fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    let mut output = [0u8; 32];
+    hasher.update(data);
+    hasher.finalize(&mut output);
+    output
+}
+
+fn namehash(name: &str) -> [u8; 32] {
+    let mut node = [0u8; 32];
+    for label in name.rsplit('.').filter(|label| !label.is_empty()) {
+        let mut buf = node.to_vec();
+        buf.extend_from_slice(&keccak256(label.as_bytes()));
+        node = keccak256(&buf);
+    }
+    node
+}
+
+/// Reads a big-endian ABI word as a usize, ignoring the high bytes.
+fn read_word(bytes: &[u8], offset: usize) -> Option<usize> {
+    let word = bytes.get(offset..offset + 32)?;
+    Some(word[24..].iter().fold(0usize, |acc, b| (acc << 8) | *b as usize))
+}
+
+/// Decodes an ABI-encoded dynamic `string` return value.
+fn decode_string(data: &str) -> Option<String> {
+    let bytes = decode_hex(data)?;
+    let offset = read_word(&bytes, 0)?;
+    let len = read_word(&bytes, offset)?;
+    let start = offset + 32;
+    String::from_utf8(bytes.get(start..start + len)?.to_vec()).ok()
+}
+
+/// Looks up the primary ENS name of an address through its reverse record.
+pub fn reverse_lookup(rpc: &RpcClient, address: &str) -> Result<Option<String>, String> {
+    let reverse_name = format!("{}.addr.reverse", address.trim_start_matches("0x").to_lowercase());
+    let node = encode_hex(&namehash(&reverse_name));
+
+    let resolver = rpc.eth_call(ENS_REGISTRY, &format!("0x{}{}", RESOLVER_SELECTOR, node))?;
+    let resolver = resolver.trim_start_matches("0x");
+    if resolver.len() < 40 || resolver.chars().all(|c| c == '0') {
+        return Ok(None);
+    }
+    let resolver = format!("0x{}", &resolver[resolver.len() - 40..]);
+
+    let name = rpc.eth_call(&resolver, &format!("0x{}{}", NAME_SELECTOR, node))?;
+    Ok(decode_string(&name).filter(|name| !name.is_empty()))
+}
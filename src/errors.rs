@@ -0,0 +1,51 @@
+//! How errors reading a single network's deployment are handled by commands
+//! that read many of them: either the run stops at the first one, or they are
+//! collected, the run goes on without those networks, and the CLI summarizes
+//! them at the end.
+
+use std::sync::Mutex;
+
+/// What to do when one network's deployment can't be read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorMode {
+    /// Stop at the first error
+    FailFast,
+    /// Skip the network, remember the error and keep going
+    #[default]
+    Collect,
+}
+
+/// The errors hit reading single networks, handled according to its mode.
+#[derive(Debug, Default)]
+pub struct ErrorLog {
+    pub mode: ErrorMode,
+    collected: Mutex<Vec<(u64, String)>>,
+}
+
+impl ErrorLog {
+    pub fn new(mode: ErrorMode) -> ErrorLog {
+        ErrorLog { mode, collected: Mutex::new(Vec::new()) }
+    }
+
+    /// Handles an error reading the deployment of `chain_id`: returned as is
+    /// in fail-fast mode, otherwise collected and handed back so the caller
+    /// can skip the network. Only the first error for each chain is kept,
+    /// since several checks may read the same deployment.
+    pub fn network_error(&self, chain_id: u64, message: String) -> Result<String, String> {
+        if self.mode == ErrorMode::FailFast {
+            return Err(message);
+        }
+        let mut collected = self.collected.lock().unwrap_or_else(|e| e.into_inner());
+        if !collected.iter().any(|(id, _)| *id == chain_id) {
+            collected.push((chain_id, message.clone()));
+        }
+        Ok(message)
+    }
+
+    /// The errors collected so far, in the order they were first hit.
+    pub fn collected(&self) -> Vec<String> {
+        self.collected.lock().unwrap_or_else(|e| e.into_inner()).iter()
+            .map(|(_, message)| message.clone())
+            .collect()
+    }
+}
@@ -0,0 +1,40 @@
+/// Block explorer base URLs for well-known chains, keyed by chain ID.
+const EXPLORERS: &[(u64, &str)] = &[
+    (1, "https://etherscan.io"),
+    (11155111, "https://sepolia.etherscan.io"),
+    (17000, "https://holesky.etherscan.io"),
+    (10, "https://optimistic.etherscan.io"),
+    (11155420, "https://sepolia-optimism.etherscan.io"),
+    (8453, "https://basescan.org"),
+    (84532, "https://sepolia.basescan.org"),
+    (42161, "https://arbiscan.io"),
+    (421614, "https://sepolia.arbiscan.io"),
+    (137, "https://polygonscan.com"),
+    (80002, "https://amoy.polygonscan.com"),
+    (1101, "https://zkevm.polygonscan.com"),
+    (56, "https://bscscan.com"),
+    (97, "https://testnet.bscscan.com"),
+    (43114, "https://snowtrace.io"),
+    (43113, "https://testnet.snowtrace.io"),
+    (250, "https://ftmscan.com"),
+    (100, "https://gnosisscan.io"),
+    (59144, "https://lineascan.build"),
+    (59141, "https://sepolia.lineascan.build"),
+    (534352, "https://scrollscan.com"),
+    (534351, "https://sepolia.scrollscan.com"),
+    (324, "https://explorer.zksync.io"),
+    (81457, "https://blastscan.io"),
+    (5000, "https://mantlescan.xyz"),
+    (42220, "https://celoscan.io"),
+];
+
+pub fn explorer_base_url(chain_id: u64) -> Option<&'static str> {
+    EXPLORERS
+        .iter()
+        .find(|(id, _)| *id == chain_id)
+        .map(|(_, url)| *url)
+}
+
+pub fn address_url(chain_id: u64, address: &str) -> Option<String> {
+    explorer_base_url(chain_id).map(|base| format!("{}/address/{}", base, address))
+}
@@ -0,0 +1,68 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Runs git inside `root`, returning stdout on success and stderr otherwise.
+fn run_git(root: &Path, args: &[&str]) -> Result<Result<String, String>, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    if output.status.success() {
+        Ok(Ok(String::from_utf8_lossy(&output.stdout).into_owned()))
+    } else {
+        Ok(Err(String::from_utf8_lossy(&output.stderr).trim().to_string()))
+    }
+}
+
+/// Checks that `git_ref` names a commit in the repository containing `root`.
+pub fn verify_ref(root: &Path, git_ref: &str) -> Result<(), String> {
+    run_git(root, &["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", git_ref)])?
+        .map(|_| ())
+        .map_err(|_| format!("Unknown git ref {}", git_ref))
+}
+
+/// Reads a file at `git_ref` without checking it out. `path` is relative to
+/// `root`; returns `None` if the file doesn't exist at that ref.
+pub fn show_file(root: &Path, git_ref: &str, path: &str) -> Result<Option<String>, String> {
+    Ok(run_git(root, &["show", &format!("{}:./{}", git_ref, path)])?.ok())
+}
+
+/// Lists the directory at `path` as it was at `git_ref`, as (name, whether
+/// it's a directory). `path` is relative to `root`; returns `None` if there is
+/// no such directory at that ref.
+pub fn list_tree(root: &Path, git_ref: &str, path: &str) -> Result<Option<Vec<(String, bool)>>, String> {
+    let Ok(output) = run_git(root, &["ls-tree", &format!("{}:./{}", git_ref, path)])? else {
+        return Ok(None);
+    };
+    let mut entries: Vec<(String, bool)> = output.lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(info, name)| (name.to_string(), info.split_whitespace().nth(1) == Some("tree")))
+        .collect();
+    entries.sort();
+    Ok(Some(entries))
+}
+
+/// Returns the abbreviated hash of the latest commit after `since` (up to
+/// HEAD) that changed `path`, or `None` if it hasn't changed since then.
+/// `path` is relative to `root`.
+pub fn last_change_since(root: &Path, since: &str, path: &str) -> Result<Option<String>, String> {
+    let output = run_git(root, &["log", "-1", "--format=%h", &format!("{}..HEAD", since), "--", &format!("./{}", path)])?
+        .map_err(|e| format!("Failed to read the git history of {}: {}", path, e))?;
+    let hash = output.trim();
+    Ok((!hash.is_empty()).then(|| hash.to_string()))
+}
+
+/// Returns the abbreviated hash and commit date (`YYYY-MM-DD`) of every
+/// commit up to HEAD that changed `path`, oldest first. `path` is relative
+/// to `root`.
+pub fn file_history(root: &Path, path: &str) -> Result<Vec<(String, String)>, String> {
+    let output = run_git(root, &["log", "--reverse", "--format=%h %cs", "HEAD", "--", &format!("./{}", path)])?
+        .map_err(|e| format!("Failed to read the git history of {}: {}", path, e))?;
+    Ok(output.lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(hash, date)| (hash.to_string(), date.to_string()))
+        .collect())
+}
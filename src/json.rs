@@ -0,0 +1,56 @@
+//! Pretty-printed JSON output. Tools consuming it disagree on the indent, so
+//! it's configurable instead of serde_json's fixed 2 spaces.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// Spaces per indentation level when nothing else is set
+pub const DEFAULT_INDENT: usize = 2;
+
+/// Serializes `value` as pretty-printed JSON, indented by `indent` spaces per
+/// level.
+pub fn to_string_pretty<T: Serialize + ?Sized>(value: &T, indent: usize) -> Result<String, String> {
+    let indent = " ".repeat(indent);
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+    let mut output = Vec::new();
+    let mut serializer = serde_json::Serializer::with_formatter(&mut output, formatter);
+    value.serialize(&mut serializer).map_err(|e| e.to_string())?;
+    String::from_utf8(output).map_err(|e| e.to_string())
+}
+
+/// Appends `records` to the JSON array in `path`, creating the file if it
+/// doesn't exist. With `dedupe`, records equal to one already in the array are
+/// skipped. The array is written to a temporary file next to `path` and moved
+/// over it, so an interrupted run never leaves it half-written. Returns the
+/// number of records appended.
+pub fn append_to_array(path: &Path, records: Vec<Value>, dedupe: bool, indent: usize) -> Result<usize, String> {
+    let mut array = if path.exists() {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        match serde_json::from_str(content.trim_start_matches('\u{feff}')) {
+            Ok(Value::Array(array)) => array,
+            Ok(_) => return Err(format!("Failed to append to {}: the file doesn't contain a JSON array", path.display())),
+            Err(e) => return Err(format!("Failed to parse {}: {}", path.display(), e)),
+        }
+    } else {
+        Vec::new()
+    };
+
+    let existing = array.len();
+    for record in records {
+        if !(dedupe && array.contains(&record)) {
+            array.push(record);
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    let file_name = path.file_name().map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+    let temp_path = path.with_file_name(format!(".{}.tmp", file_name));
+    fs::write(&temp_path, to_string_pretty(&array, indent)?).map_err(|e| format!("Failed to write to file: {}", e))?;
+    fs::rename(&temp_path, path).map_err(|e| format!("Failed to write to file: {}", e))?;
+    Ok(array.len() - existing)
+}
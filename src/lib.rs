@@ -0,0 +1,4084 @@
+//! Core analysis of hardhat deployments: config parsing, reading deployment
+//! records and gathering the data behind the CLI's reports.
+
+pub mod config;
+pub mod context;
+pub mod ens;
+pub mod errors;
+pub mod explorer;
+pub mod git;
+pub mod json;
+pub mod registry;
+pub mod rpc;
+pub mod source;
+pub mod timing;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+pub use context::Context;
+use source::{DeploymentSource, GitRef};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::num::IntErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Checks that `root` looks like a hardhat project.
+pub fn validate_hardhat_project(ctx: &Context) -> Result<(), String> {
+    if !ctx.config_exists() {
+        return match ctx.config_path() {
+            Some(path) if path != ctx.root.join(config::DEFAULT_CONFIG_FILE) => {
+                Err(format!("Config file {} not found", path.display()))
+            }
+            _ => Err(format!("No {} found in the specified root directory", ctx.config_name())),
+        };
+    }
+    Ok(())
+}
+
+/// Stricter than [`validate_hardhat_project`]: the config must exist and at
+/// least one network (other than `hardhat`) must be parsed from it, so a
+/// broken config can't pass as a project without networks.
+pub fn require_config_networks(ctx: &Context) -> Result<(), String> {
+    validate_hardhat_project(ctx)?;
+    if parse_hardhat_config(ctx)?.keys().all(|network| network == "hardhat") {
+        return Err(format!("No networks with a chainId could be parsed from {}", ctx.config_name()));
+    }
+    Ok(())
+}
+
+/// Drops the UTF-8 byte order mark some Windows editors put at the start of a
+/// file, which serde_json refuses to parse.
+fn strip_bom(content: &str) -> &str {
+    content.strip_prefix('\u{feff}').unwrap_or(content)
+}
+
+/// Blanks out `//` and `/* */` comments in a config, so commented-out
+/// settings such as `// chainId: 1 (old)` aren't parsed. Comment markers in
+/// string and template literals, like the `//` of a URL, are left alone, and
+/// line breaks are kept.
+fn strip_comments(content: &str) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut quote = None;
+
+    while let Some(c) = chars.next() {
+        if let Some(open) = quote {
+            output.push(c);
+            if c == '\\' {
+                output.extend(chars.next());
+            } else if c == open {
+                quote = None;
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('/', Some('/')) => while chars.next_if(|&c| c != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = None;
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        output.push('\n');
+                    }
+                    if previous == Some('*') && c == '/' {
+                        break;
+                    }
+                    previous = Some(c);
+                }
+                output.push(' ');
+            }
+            ('"' | '\'' | '`', _) => {
+                quote = Some(c);
+                output.push(c);
+            }
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+/// Returns the path of the project's `deployments` directory, which may not
+/// exist yet. Errors when `deployments` exists but isn't a directory, so a
+/// misconfigured project isn't mistaken for one without deployments.
+pub fn deployments_dir(ctx: &Context) -> Result<PathBuf, String> {
+    let deployments_dir = ctx.root.join("deployments");
+    if deployments_dir.exists() && !deployments_dir.is_dir() {
+        return Err(format!("{} exists but is not a directory", deployments_dir.display()));
+    }
+    Ok(deployments_dir)
+}
+
+/// Counts the deployment directories inside `deployments/`.
+pub fn count_deployments(ctx: &Context) -> Result<usize, String> {
+    let entries = ctx.list_dir(&deployments_dir(ctx)?)
+        .map_err(|e| format!("Failed to read deployments directory: {}", e))?;
+    Ok(entries.unwrap_or_default().iter()
+        .filter(|(name, is_dir)| *is_dir && !ctx.is_ignored_dir_name(name))
+        .count())
+}
+
+/// Turns a camelCase network name into a display name, e.g. `baseSepolia`
+/// becomes `Base Sepolia`.
+pub fn camel_to_title_case(s: &str) -> String {
+    let re = Regex::new(r"([a-z0-9])([A-Z])").unwrap();
+    let spaced = re.replace_all(s, "$1 $2").to_string();
+    spaced.split_whitespace()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                None => String::new(),
+                Some(c) => c.to_uppercase().chain(chars).collect(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Turns a network name into a lowercase slug with words separated by
+/// hyphens, e.g. `baseSepolia`, `base_sepolia` and `Base Sepolia` all become
+/// `base-sepolia`.
+pub fn network_slug(network: &str) -> String {
+    let re = Regex::new(r"([a-z0-9])([A-Z])").unwrap();
+    let spaced = re.replace_all(network, "$1 $2").to_lowercase();
+    spaced.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Parses the network names and chain IDs declared in `hardhat.config.ts`.
+/// Chain IDs may be written as BigInt literals (e.g. `11155111n`) or in hex
+/// (e.g. `0x2105`).
+///
+/// Networks are usually an object keyed by name (`base: { chainId: 8453 }`).
+/// When no network is declared that way, the config is read as an array of
+/// objects with `name` and `chainId` fields (`{ name: "base", chainId: 8453 }`).
+///
+/// Networks from a `network-config.json` next to the config are merged in,
+/// see [`parse_network_config_json`]. The config wins when both declare a
+/// network.
+pub fn parse_hardhat_config(ctx: &Context) -> Result<HashMap<String, u64>, String> {
+    ctx.timings.time("config parsing", || {
+        let content = ctx.read_config()?;
+        let mut networks = parse_config_networks(&content)?;
+
+        for (network_name, chain_id) in parse_network_config_json(ctx)? {
+            networks.entry(network_name).or_insert(chain_id);
+        }
+
+        Ok(networks)
+    })
+}
+
+/// Parses the networks declared in the content of a hardhat config, without
+/// merging in `network-config.json`. See [`parse_hardhat_config`].
+pub fn parse_config_networks(content: &str) -> Result<HashMap<String, u64>, String> {
+    let content = strip_comments(strip_bom(content));
+    let content = content.as_str();
+
+    // Chain IDs can also name a numeric constant, e.g. `chainId: BASE_ID`
+    // after `const BASE_ID = 8453;`. References to anything else are skipped.
+    let constant_regex = Regex::new(r#"\bconst\s+([A-Za-z_]\w*)\s*(?::\s*\w+\s*)?=\s*(0[xX][0-9a-fA-F]+|\d+)n?\b"#).unwrap();
+    let constants: HashMap<&str, &str> = constant_regex.captures_iter(content)
+        .map(|cap| (cap.get(1).unwrap().as_str(), cap.get(2).unwrap().as_str()))
+        .collect();
+    let resolve = |network_name: &str, value: &str| -> Result<Option<u64>, String> {
+        if value.starts_with(|c: char| c.is_ascii_digit()) {
+            return parse_chain_id(network_name, value).map(Some);
+        }
+        constants.get(value).map(|digits| parse_chain_id(network_name, digits)).transpose()
+    };
+
+    let mut networks = HashMap::new();
+    // Template literals are skipped whole, since `${...}` interpolations
+    // (e.g. in a url before the chainId) contain braces
+    let network_regex = Regex::new(r#"(\w+):\s*\{(?:[^}`]|`[^`]*`)*chainId:\s*(0[xX][0-9a-fA-F]+|\d+|[A-Za-z_]\w*)n?\b"#).unwrap();
+
+    for cap in network_regex.captures_iter(content) {
+        let network_name = cap[1].to_string();
+        if let Some(chain_id) = resolve(&network_name, &cap[2])? {
+            networks.insert(network_name, chain_id);
+        }
+    }
+
+    if networks.is_empty() {
+        let entry_regex = Regex::new(r#"\{[^{}]*\}"#).unwrap();
+        let name_regex = Regex::new(r#"\bname:\s*["'](\w+)["']"#).unwrap();
+        let chain_id_regex = Regex::new(r#"\bchainId:\s*(0[xX][0-9a-fA-F]+|\d+|[A-Za-z_]\w*)n?\b"#).unwrap();
+
+        for entry in entry_regex.find_iter(content) {
+            let entry = entry.as_str();
+            if let (Some(name), Some(chain_id)) = (name_regex.captures(entry), chain_id_regex.captures(entry)) {
+                let network_name = name[1].to_string();
+                if let Some(chain_id) = resolve(&network_name, &chain_id[1])? {
+                    networks.insert(network_name, chain_id);
+                }
+            }
+        }
+    }
+
+    Ok(networks)
+}
+
+/// Reads the networks of a `network-config.json` in the project root, for
+/// configs that load their network definitions from JSON. Each network maps
+/// either to its chain ID or to an object with a `chainId` field; networks
+/// without a chain ID are skipped. Returns nothing when the file doesn't exist.
+pub fn parse_network_config_json(ctx: &Context) -> Result<HashMap<String, u64>, String> {
+    let Some(content) = ctx.read_file(&ctx.root.join("network-config.json"))? else {
+        return Ok(HashMap::new());
+    };
+    let value: Value = serde_json::from_str(strip_bom(&content))
+        .map_err(|e| format!("Failed to parse network-config.json: {}", e))?;
+    let entries = value.as_object()
+        .ok_or("network-config.json must be an object keyed by network name")?;
+
+    let mut networks = HashMap::new();
+    for (network_name, entry) in entries {
+        let chain_id = match entry.get("chainId").unwrap_or(entry) {
+            Value::Number(number) => number.to_string(),
+            Value::String(digits) => digits.trim_end_matches('n').to_string(),
+            _ => continue,
+        };
+        networks.insert(network_name.clone(), parse_chain_id(network_name, &chain_id)?);
+    }
+
+    Ok(networks)
+}
+
+fn parse_chain_id(network_name: &str, digits: &str) -> Result<u64, String> {
+    let parsed = match digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => digits.parse::<u64>(),
+    };
+    parsed.map_err(|e| match e.kind() {
+        IntErrorKind::PosOverflow => format!(
+            "Chain ID {} for network {} is too large (the maximum is {})",
+            digits, network_name, u64::MAX
+        ),
+        _ => format!("Invalid chain ID for network {}", network_name),
+    })
+}
+
+/// Parses the `defaultNetwork` declared in `hardhat.config.ts`, if any.
+pub fn parse_default_network(ctx: &Context) -> Result<Option<String>, String> {
+    ctx.timings.time("config parsing", || {
+        let content = ctx.read_config()?;
+        let content = strip_comments(strip_bom(&content));
+        let content = content.as_str();
+
+        let default_regex = Regex::new(r#"defaultNetwork:\s*["'](\w+)["']"#).unwrap();
+        Ok(default_regex.captures(content).map(|cap| cap[1].to_string()))
+    })
+}
+
+/// An RPC URL read from the hardhat config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigUrl {
+    pub url: String,
+    /// Whether the URL is a template literal with `${...}` interpolations,
+    /// which are left unresolved
+    pub partial: bool,
+}
+
+/// Parses the RPC URLs of the networks in `hardhat.config.ts`. String and
+/// template literals are read; URLs built from expressions such as
+/// `process.env.RPC_URL` are left out.
+pub fn parse_network_urls(ctx: &Context) -> Result<HashMap<String, ConfigUrl>, String> {
+    let content = ctx.read_config()?;
+    let content = strip_comments(strip_bom(&content));
+    let content = content.as_str();
+
+    let url_regex = Regex::new(r#"(\w+):\s*\{(?:[^}`]|`[^`]*`)*\burl:\s*(?:["']([^"']+)["']|`([^`]+)`)"#).unwrap();
+    Ok(url_regex.captures_iter(content)
+        .map(|cap| {
+            let url = match cap.get(2) {
+                Some(literal) => ConfigUrl { url: literal.as_str().to_string(), partial: false },
+                None => ConfigUrl { url: cap[3].to_string(), partial: cap[3].contains("${") },
+            };
+            (cap[1].to_string(), url)
+        })
+        .collect())
+}
+
+/// Shown in place of every address with `--redact-addresses`, so a report
+/// can be shared without revealing where anything is deployed.
+pub const REDACTED_ADDRESS: &str = "0x****...****";
+
+/// (contract, address) pairs recorded for a single network
+pub type ContractEntries = Vec<(String, String)>;
+
+/// Reads every (contract, address) pair from a chain directory's
+/// `deployed_addresses.json`, or `None` if the file doesn't exist. Addresses
+/// can be plain strings or objects with an `address` field (e.g.
+/// `{"address": "0x...", "abi": [...]}`).
+pub fn get_deployment_contracts(ctx: &Context, deployment_dir: &Path) -> Result<Option<ContractEntries>, String> {
+    match ctx.read_file(&deployment_dir.join("deployed_addresses.json"))? {
+        Some(content) => parse_deployed_addresses(&content),
+        None => Ok(None),
+    }
+}
+
+/// Parses the contents of a `deployed_addresses.json` into (contract, address)
+/// pairs, see [`get_deployment_contracts`]. Whitespace around an address is
+/// trimmed, see [`find_padded_entries`].
+pub fn parse_deployed_addresses(content: &str) -> Result<Option<ContractEntries>, String> {
+    Ok(parse_untrimmed_addresses(content)?.map(|contracts| {
+        contracts.into_iter()
+            .map(|(name, address)| (name, address.trim().to_string()))
+            .collect()
+    }))
+}
+
+/// The (contract, address) pairs in the contents of a `deployed_addresses.json`
+/// whose address has leading or trailing whitespace, with the address as
+/// written.
+pub fn find_padded_entries(content: &str) -> Result<ContractEntries, String> {
+    Ok(parse_untrimmed_addresses(content)?.unwrap_or_default().into_iter()
+        .filter(|(_, address)| address.trim() != address)
+        .collect())
+}
+
+fn parse_untrimmed_addresses(content: &str) -> Result<Option<ContractEntries>, String> {
+    let parse_error = |e: serde_json::Error| format!("Failed to parse deployed_addresses.json: {}", e);
+    let data: Value = serde_json::from_str(strip_bom(content)).map_err(parse_error)?;
+    if !data.is_object() {
+        return Ok(None);
+    }
+
+    // Read the entries again in file order, which `Value` doesn't keep
+    let OrderedEntries(entries) = serde_json::from_str(strip_bom(content)).map_err(parse_error)?;
+    Ok(Some(entries.into_iter()
+        .filter_map(|(name, v)| {
+            v.as_str()
+                .or_else(|| v.get("address").and_then(Value::as_str))
+                .map(|address| (name, address.to_string()))
+        })
+        .collect()))
+}
+
+/// The entries of a JSON object in the order they're written, so contracts
+/// are listed (and the first contract picked) in the order Ignition recorded
+/// them. A repeated key keeps its first position and its last value, like
+/// `serde_json::Map`.
+struct OrderedEntries(Vec<(String, Value)>);
+
+impl<'de> Deserialize<'de> for OrderedEntries {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EntriesVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for EntriesVisitor {
+            type Value = OrderedEntries;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a JSON object")
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<OrderedEntries, A::Error> {
+                let mut entries: Vec<(String, Value)> = Vec::new();
+                while let Some((key, value)) = map.next_entry::<String, Value>()? {
+                    match entries.iter_mut().find(|(existing, _)| *existing == key) {
+                        Some(entry) => entry.1 = value,
+                        None => entries.push((key, value)),
+                    }
+                }
+                Ok(OrderedEntries(entries))
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor)
+    }
+}
+
+/// Reads the first address recorded in a chain directory's
+/// `deployed_addresses.json`.
+pub fn get_deployment_address(ctx: &Context, deployment_dir: &Path) -> Result<Option<String>, String> {
+    // Get the first address we find (assuming there's at least one)
+    Ok(get_deployment_contracts(ctx, deployment_dir)?
+        .and_then(|contracts| contracts.into_iter().next())
+        .map(|(_, address)| address))
+}
+
+/// Splits an Ignition `Module#Contract` key into its module and contract
+/// name. Keys without a module are returned whole.
+pub fn split_contract_key(key: &str) -> (Option<&str>, &str) {
+    match key.split_once('#') {
+        Some((module, contract)) => (Some(module), contract),
+        None => (None, key),
+    }
+}
+
+/// Matches a deployed contract key against a user supplied name, accepting
+/// either the full `Module#Contract` key or just the contract name. A bare
+/// name can match several keys when Ignition modules deploy contracts of the
+/// same name; use [`find_contract`] to pick a single contract.
+pub fn contract_matches(key: &str, name: &str) -> bool {
+    key.eq_ignore_ascii_case(name)
+        || key.rsplit('#').next().is_some_and(|contract| contract.eq_ignore_ascii_case(name))
+}
+
+/// Finds the contract of `contracts` a user supplied name refers to, see
+/// [`contract_matches`]. A full key always picks its contract, but a bare
+/// contract name deployed by several modules is an error listing their keys.
+pub fn find_contract<'a>(contracts: &'a [(String, String)], name: &str) -> Result<Option<&'a (String, String)>, String> {
+    if let Some(entry) = contracts.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)) {
+        return Ok(Some(entry));
+    }
+    let matches: Vec<&(String, String)> = contracts.iter().filter(|(key, _)| contract_matches(key, name)).collect();
+    match matches[..] {
+        [] => Ok(None),
+        [entry] => Ok(Some(entry)),
+        _ => Err(format!(
+            "Contract {} is ambiguous, it matches {}",
+            name,
+            matches.iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>().join(", ")
+        )),
+    }
+}
+
+/// Resolves the chain ID and address of a contract deployed to `network`,
+/// falling back to the first contract when none is given.
+pub fn resolve_deployment(ctx: &Context, network: &str, contract: Option<&str>) -> Result<(u64, String), String> {
+    let (chain_id, _, (_, address)) = find_deployed_contract(ctx, network, contract)?;
+    Ok((chain_id, address))
+}
+
+/// A commit that changed the address of a contract, see
+/// [`contract_history`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContractHistoryEntry {
+    pub commit: String,
+    /// Commit date, `YYYY-MM-DD`
+    pub date: String,
+    /// `None` when the commit removed the contract from the deployment
+    pub address: Option<String>,
+}
+
+/// Walks the git history of the `deployed_addresses.json` of `network`,
+/// returning each commit that changed the address of `contract`, oldest
+/// first. Changes that aren't committed yet don't count.
+pub fn contract_history(ctx: &Context, network: &str, contract: &str) -> Result<Vec<ContractHistoryEntry>, String> {
+    ctx.require_working_tree("history")?;
+    let networks = parse_hardhat_config(ctx)?;
+    let chain_id = *networks.get(network)
+        .ok_or_else(|| format!("Network {} not found in {}", network, ctx.config_name()))?;
+
+    let path = format!("deployments/chain-{}/deployed_addresses.json", chain_id);
+    let mut history: Vec<ContractHistoryEntry> = Vec::new();
+    for (commit, date) in git::file_history(&ctx.root, &path)? {
+        let address = match git::show_file(&ctx.root, &commit, &path)? {
+            Some(content) => match parse_deployed_addresses(&content).map_err(|e| format!("{} at commit {}", e, commit))? {
+                Some(contracts) => find_contract(&contracts, contract)
+                    .map_err(|e| format!("{} at commit {}", e, commit))?
+                    .map(|(_, address)| address.clone()),
+                None => None,
+            },
+            None => None,
+        };
+        let previous = history.last().map(|entry| entry.address.as_ref());
+        if previous.map_or(address.is_some(), |previous| previous != address.as_ref()) {
+            history.push(ContractHistoryEntry { commit, date, address });
+        }
+    }
+
+    if history.is_empty() {
+        return Err(format!("Contract {} not found in the git history of network {}", contract, network));
+    }
+    Ok(history)
+}
+
+/// Finds the chain ID, deployment directory and (key, address) entry of a
+/// contract deployed to `network`, see [`resolve_deployment`].
+fn find_deployed_contract(ctx: &Context, network: &str, contract: Option<&str>) -> Result<(u64, PathBuf, (String, String)), String> {
+    let networks = parse_hardhat_config(ctx)?;
+    let chain_id = *networks.get(network)
+        .ok_or_else(|| format!("Network {} not found in {}", network, ctx.config_name()))?;
+
+    let chain_dir = deployments_dir(ctx)?.join(format!("chain-{}", chain_id));
+    let contracts = get_deployment_contracts(ctx, &chain_dir)?
+        .ok_or_else(|| format!("No deployment found for network {}", network))?;
+
+    let entry = match contract {
+        Some(name) => find_contract(&contracts, name)
+            .map_err(|e| format!("{} on network {}", e, network))?
+            .cloned()
+            .ok_or_else(|| format!("Contract {} not found in deployment for network {}", name, network))?,
+        None => contracts.into_iter()
+            .next()
+            .ok_or_else(|| format!("No contracts found in deployment for network {}", network))?,
+    };
+
+    Ok((chain_id, chain_dir, entry))
+}
+
+/// Files behind a deployed contract, for jumping from a deployment to its code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContractLocation {
+    /// Key of the contract in `deployed_addresses.json`
+    pub contract: String,
+    /// Solidity source, if the artifact's build-info names one
+    pub source: Option<PathBuf>,
+    /// Ignition artifact JSON, if the deployment has one
+    pub artifact: Option<PathBuf>,
+}
+
+/// Locates the Solidity source and artifact JSON of a contract deployed to
+/// `network`, falling back to the first contract when none is given. Sources
+/// are resolved against the project root, or `node_modules` for library
+/// sources (e.g. `@openzeppelin/...`) that aren't in the project.
+pub fn locate_contract(ctx: &Context, network: &str, contract: Option<&str>) -> Result<ContractLocation, String> {
+    let (_, chain_dir, (key, _)) = find_deployed_contract(ctx, network, contract)?;
+
+    let source = resolve_contract_source(ctx, &chain_dir, &key).map(|source| {
+        let path = ctx.root.join(&source);
+        let library_path = ctx.root.join("node_modules").join(&source);
+        if !ctx.is_file(&path) && ctx.is_file(&library_path) { library_path } else { path }
+    });
+    let artifact = Some(chain_dir.join("artifacts").join(format!("{}.json", key)))
+        .filter(|path| ctx.is_file(path));
+
+    Ok(ContractLocation { contract: key, source, artifact })
+}
+
+/// Resolves the Solidity source path of a deployed contract through the
+/// Ignition artifact's debug file and the build-info it points at.
+pub fn resolve_contract_source(ctx: &Context, deployment_dir: &Path, contract_key: &str) -> Option<String> {
+    let artifacts_dir = deployment_dir.join("artifacts");
+    let dbg: Value = serde_json::from_str(
+        &ctx.read_file(&artifacts_dir.join(format!("{}.dbg.json", contract_key))).ok()??
+    ).ok()?;
+    let build_info_path = artifacts_dir.join(dbg["buildInfo"].as_str()?);
+    let build_info: Value = serde_json::from_str(&ctx.read_file(&build_info_path).ok()??).ok()?;
+
+    let contract_name = contract_key.rsplit('#').next().unwrap_or(contract_key);
+    build_info["output"]["contracts"]
+        .as_object()?
+        .iter()
+        .find(|(_, contracts)| contracts.get(contract_name).is_some())
+        .map(|(source, _)| source.clone())
+}
+
+/// Splits a camelCase network name into its ecosystem prefix and network
+/// suffix, e.g. `baseSepolia` becomes (`base`, `Sepolia`) and `base` becomes
+/// (`base`, `Mainnet`).
+pub fn split_network_name(network: &str) -> (String, String) {
+    let parts: Vec<&str> = network.split(|c: char| c.is_uppercase()).collect();
+    let prefix = parts[0].to_string();
+    let suffix = network[prefix.len()..].to_string();
+
+    let suffix = if suffix.is_empty() {
+        "Mainnet".to_string()
+    } else {
+        suffix
+    };
+
+    (prefix, suffix)
+}
+
+/// Orders network suffixes alphabetically with `Mainnet` always first.
+pub fn compare_suffixes(a: &str, b: &str) -> std::cmp::Ordering {
+    if a == "Mainnet" {
+        std::cmp::Ordering::Less
+    } else if b == "Mainnet" {
+        std::cmp::Ordering::Greater
+    } else {
+        a.cmp(b)
+    }
+}
+
+/// Groups `items` by the ecosystem prefix of their `network` (see
+/// [`split_network_name`]) into (prefix, [(suffix, item)]), keeping the order
+/// of the items within each group. Groups are ordered for `group_sort`: by
+/// prefix, by number of networks (largest first), or by the oldest deployment
+/// time `deployed_at` gives for their items. Ties, and groups without a known
+/// time, keep prefix order.
+pub fn group_by_ecosystem<T>(items: impl IntoIterator<Item = T>, network: impl Fn(&T) -> &str, group_sort: &str, deployed_at: impl Fn(&T) -> Option<SystemTime>) -> Vec<(String, Vec<(String, T)>)> {
+    let mut grouped: BTreeMap<String, Vec<(String, T)>> = BTreeMap::new();
+    for item in items {
+        let (prefix, suffix) = split_network_name(network(&item));
+        grouped.entry(prefix).or_default().push((suffix, item));
+    }
+    let mut groups: Vec<(String, Vec<(String, T)>)> = grouped.into_iter().collect();
+    match group_sort {
+        "count" => groups.sort_by_key(|(_, networks)| std::cmp::Reverse(networks.len())),
+        "first-deployed" => groups.sort_by_cached_key(|(_, networks)| {
+            let first = networks.iter().filter_map(|(_, item)| deployed_at(item)).min();
+            (first.is_none(), first)
+        }),
+        _ => {}
+    }
+    groups
+}
+
+/// Groups network names that only differ by case or whitespace, which usually
+/// indicates a typo in the config (e.g. `baseSepolia` and `basesepolia`).
+pub fn find_similar_network_names(networks: &HashMap<String, u64>) -> Vec<SimilarNetworkNames> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for network_name in networks.keys() {
+        let normalized: String = network_name.chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(|c| c.to_lowercase())
+            .collect();
+        groups.entry(normalized).or_default().push(network_name.clone());
+    }
+
+    groups.into_iter()
+        .filter(|(_, names)| names.len() > 1)
+        .map(|(normalized, mut networks)| {
+            networks.sort();
+            SimilarNetworkNames { normalized, networks }
+        })
+        .collect()
+}
+
+/// Which contracts of each deployment a list report should include.
+#[derive(Clone, Copy)]
+pub enum ContractSelection<'a> {
+    /// Only the first contract recorded for each network
+    First,
+    /// Only the contract whose full `Module#Contract` key or contract name
+    /// matches the regex, falling back to the first contract for networks
+    /// without one. Several matching contracts are an error.
+    Primary(&'a Regex),
+    /// Every contract recorded for each network
+    All,
+    /// Contracts matching any of the given names (see [`contract_matches`])
+    Matching(&'a [String]),
+    /// Contracts whose full `Module#Contract` key or contract name matches
+    /// the regex
+    Pattern(&'a Regex),
+}
+
+/// A contract recorded in a deployment.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeployedContract {
+    /// Empty for addresses from a resolver command, which don't name contracts
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    pub address: String,
+    /// Whether the address came from an aliases file rather than a deployment
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub external: bool,
+    /// Tags from `deployments-meta.toml`, see [`ListReport::add_tags`]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Friendly name from an alias map, see [`ListReport::apply_alias_map`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+}
+
+impl DeployedContract {
+    /// The contract name (or its alias), marked with `(external)` for aliased
+    /// contracts.
+    pub fn display_name(&self) -> String {
+        let name = self.alias.as_deref().unwrap_or(&self.name);
+        if self.external {
+            format!("{} (external)", name)
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// Like [`Self::display_name`], but without the Ignition module of the
+    /// key, unless another of `contracts` (the contracts of the same
+    /// deployment) has the same contract name and only the module tells them
+    /// apart.
+    pub fn display_name_without_module(&self, contracts: &[DeployedContract]) -> String {
+        let contract = split_contract_key(&self.name).1;
+        let shared = contracts.iter()
+            .filter(|other| split_contract_key(&other.name).1.eq_ignore_ascii_case(contract))
+            .count() > 1;
+        let name = match &self.alias {
+            Some(alias) => alias.as_str(),
+            None if shared => &self.name,
+            None => contract,
+        };
+        if self.external {
+            format!("{} (external)", name)
+        } else {
+            name.to_string()
+        }
+    }
+}
+
+/// The selected contracts deployed to a configured network.
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkDeployment {
+    pub network: String,
+    pub chain_id: u64,
+    pub contracts: Vec<DeployedContract>,
+    /// Latest commit that changed the deployed addresses, see
+    /// [`ListReport::retain_changed_since`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+/// Role of a contract in an upgradeable proxy setup, guessed from its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyRole {
+    Proxy,
+    Admin,
+    Implementation,
+}
+
+/// Classifies a contract key by name: `...ProxyAdmin` contracts are admins,
+/// other contracts with `Proxy` in their name are proxies, and everything
+/// else could be an implementation.
+pub fn classify_proxy_role(key: &str) -> ProxyRole {
+    let (_, name) = split_contract_key(key);
+    if name.ends_with("ProxyAdmin") {
+        ProxyRole::Admin
+    } else if name.contains("Proxy") {
+        ProxyRole::Proxy
+    } else {
+        ProxyRole::Implementation
+    }
+}
+
+/// An edge of the proxy graph: `from` points at its implementation, or
+/// administers `to`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProxyLink {
+    pub from: String,
+    pub to: String,
+    pub role: ProxyRole,
+}
+
+/// Links the proxies of a deployment to their implementations and admins.
+/// Contracts are only linked within their Ignition module: a proxy points at
+/// every implementation in its module, and an admin at every proxy. Contracts
+/// recorded at a proxy's address (Ignition's `contractAt` of the proxy) are
+/// the proxy itself, not an implementation.
+pub fn proxy_links(deployment: &NetworkDeployment) -> Vec<ProxyLink> {
+    let mut modules: BTreeMap<&str, Vec<(&DeployedContract, ProxyRole)>> = BTreeMap::new();
+    for contract in &deployment.contracts {
+        let (module, _) = split_contract_key(&contract.name);
+        modules.entry(module.unwrap_or_default()).or_default().push((contract, classify_proxy_role(&contract.name)));
+    }
+
+    let mut links = Vec::new();
+    for contracts in modules.values() {
+        let proxies: Vec<&DeployedContract> = contracts.iter()
+            .filter(|(_, role)| *role == ProxyRole::Proxy)
+            .map(|(contract, _)| *contract)
+            .collect();
+        for (contract, role) in contracts {
+            match role {
+                ProxyRole::Proxy => {}
+                ProxyRole::Admin => links.extend(proxies.iter().map(|proxy| ProxyLink {
+                    from: contract.name.clone(),
+                    to: proxy.name.clone(),
+                    role: ProxyRole::Admin,
+                })),
+                ProxyRole::Implementation => {
+                    if proxies.iter().any(|proxy| proxy.address.eq_ignore_ascii_case(&contract.address)) {
+                        continue;
+                    }
+                    links.extend(proxies.iter().map(|proxy| ProxyLink {
+                        from: proxy.name.clone(),
+                        to: contract.name.clone(),
+                        role: ProxyRole::Implementation,
+                    }));
+                }
+            }
+        }
+    }
+    links
+}
+
+/// A configured network without a deployment.
+#[derive(Debug, Clone, Serialize)]
+pub struct MissingNetwork {
+    pub network: String,
+    pub chain_id: u64,
+}
+
+/// Why a network has no deployment to list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MissingReason {
+    NoDirectory,
+    NoAddressesFile,
+    EmptyAddresses,
+    NoMatchingContracts,
+}
+
+impl MissingReason {
+    pub fn description(self, chain_id: u64) -> String {
+        match self {
+            MissingReason::NoDirectory => format!("no chain-{} directory", chain_id),
+            MissingReason::NoAddressesFile => "directory exists but no deployed_addresses.json".to_string(),
+            MissingReason::EmptyAddresses => "deployed_addresses.json is empty".to_string(),
+            MissingReason::NoMatchingContracts => "no contract matches the selection".to_string(),
+        }
+    }
+}
+
+/// Works out why the deployment of `chain_id` is missing from a listing of
+/// the working tree.
+pub fn explain_missing_deployment(ctx: &Context, chain_id: u64) -> Result<MissingReason, String> {
+    let chain_dir = deployments_dir(ctx)?.join(format!("chain-{}", chain_id));
+    if ctx.list_dir(&chain_dir)?.is_none() {
+        return Ok(MissingReason::NoDirectory);
+    }
+    if !ctx.is_file(&chain_dir.join("deployed_addresses.json")) {
+        return Ok(MissingReason::NoAddressesFile);
+    }
+    Ok(match get_deployment_contracts(ctx, &chain_dir) {
+        Ok(Some(contracts)) if !contracts.is_empty() => MissingReason::NoMatchingContracts,
+        _ => MissingReason::EmptyAddresses,
+    })
+}
+
+/// Deployments found for the networks declared in a hardhat config, sorted
+/// by network name.
+#[derive(Debug, Default, Serialize)]
+pub struct ListReport {
+    /// Networks with a deployment, alongside the selected contracts
+    pub deployments: Vec<NetworkDeployment>,
+    /// Networks without a deployment (or without any selected contract)
+    pub missing: Vec<MissingNetwork>,
+    /// Names passed to [`ContractSelection::Matching`] that matched nothing
+    pub unmatched_contracts: Vec<String>,
+    /// Issues that don't stop the report, such as aliases for unknown networks
+    pub warnings: Vec<String>,
+    /// Deployments that couldn't be read and were skipped, see
+    /// [`ErrorLog::network_error`](errors::ErrorLog::network_error)
+    pub errors: Vec<String>,
+    /// The config's `defaultNetwork`, unless it's the local `hardhat` network
+    pub default_network: Option<String>,
+    /// How many networks (besides `hardhat`) were parsed from the config. Zero
+    /// usually means the config is written in a form the parser doesn't understand
+    pub configured_networks: usize,
+}
+
+/// How many networks are configured and deployed to, and how many contracts
+/// the deployments hold in total.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeploymentCounts {
+    pub configured_networks: usize,
+    pub deployed_networks: usize,
+    pub total_contracts: usize,
+}
+
+impl ListReport {
+    /// Counts the report's networks and contracts. Every contract is only
+    /// counted when the report was built with [`ContractSelection::All`].
+    pub fn counts(&self) -> DeploymentCounts {
+        DeploymentCounts {
+            configured_networks: self.configured_networks,
+            deployed_networks: self.deployments.len(),
+            total_contracts: self.deployments.iter().map(|deployment| deployment.contracts.len()).sum(),
+        }
+    }
+
+    /// Records the selected contracts of a network's deployment, or the network
+    /// as missing when none were selected.
+    fn push_deployment(&mut self, network: String, chain_id: u64, selected: ContractEntries) {
+        if selected.is_empty() {
+            self.missing.push(MissingNetwork { network, chain_id });
+        } else {
+            self.deployments.push(NetworkDeployment {
+                network,
+                chain_id,
+                contracts: selected.into_iter()
+                    .map(|(name, address)| DeployedContract { name, address, external: false, tags: Vec::new(), alias: None })
+                    .collect(),
+                commit: None,
+            });
+        }
+    }
+
+    /// Attaches the tags of each deployed contract, matching contracts like
+    /// [`contract_matches`] so either the full key or the contract name works.
+    pub fn add_tags(&mut self, tags: &DeploymentTags) {
+        for deployment in &mut self.deployments {
+            let Some(network_tags) = tags.get(&deployment.network) else {
+                continue;
+            };
+            for contract in &mut deployment.contracts {
+                for (name, contract_tags) in network_tags {
+                    if contract_matches(&contract.name, name) {
+                        contract.tags.extend(contract_tags.iter().cloned());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Keeps only the networks named in `networks`, deployed or missing. Names
+    /// matching no network are added to the warnings.
+    pub fn retain_networks(&mut self, networks: &[String]) {
+        for name in networks {
+            let known = self.deployments.iter().any(|deployment| deployment.network == *name)
+                || self.missing.iter().any(|missing| missing.network == *name);
+            if !known {
+                self.warnings.push(format!("Network {} not found", name));
+            }
+        }
+        self.deployments.retain(|deployment| networks.contains(&deployment.network));
+        self.missing.retain(|missing| networks.contains(&missing.network));
+    }
+
+    /// Keeps only the deployments whose `deployed_addresses.json` was changed
+    /// by a commit after `since`, recording the latest such commit. Changes
+    /// that aren't committed yet don't count.
+    pub fn retain_changed_since(&mut self, ctx: &Context, since: &str) -> Result<(), String> {
+        ctx.require_working_tree("--since-commit")?;
+        git::verify_ref(&ctx.root, since)?;
+        let mut changed = Vec::new();
+        for mut deployment in self.deployments.drain(..) {
+            let path = format!("deployments/chain-{}/deployed_addresses.json", deployment.chain_id);
+            if let Some(commit) = git::last_change_since(&ctx.root, since, &path)? {
+                deployment.commit = Some(commit);
+                changed.push(deployment);
+            }
+        }
+        self.deployments = changed;
+        Ok(())
+    }
+
+    /// Renames every network to its [`network_slug`], warning when several
+    /// networks end up with the same slug.
+    pub fn normalize_network_names(&mut self) {
+        let mut original_names: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut normalize = |network: &mut String| {
+            let slug = network_slug(network);
+            original_names.entry(slug.clone()).or_default().push(std::mem::replace(network, slug));
+        };
+        for deployment in &mut self.deployments {
+            normalize(&mut deployment.network);
+        }
+        for missing in &mut self.missing {
+            normalize(&mut missing.network);
+        }
+        self.default_network = self.default_network.as_deref().map(network_slug);
+
+        for (slug, names) in original_names {
+            if names.len() > 1 {
+                self.warnings.push(format!("Networks {} all normalize to {}", names.join(", "), slug));
+            }
+        }
+        self.deployments.sort_by(|a, b| a.network.cmp(&b.network));
+        self.missing.sort_by(|a, b| a.network.cmp(&b.network));
+    }
+
+    /// Sorts the contracts of each deployment by name, instead of the order
+    /// they're recorded in.
+    pub fn sort_contracts(&mut self) {
+        for deployment in &mut self.deployments {
+            deployment.contracts.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+    }
+
+    /// Replaces every address with [`REDACTED_ADDRESS`], keeping the networks
+    /// and contracts.
+    pub fn redact_addresses(&mut self) {
+        for deployment in &mut self.deployments {
+            for contract in &mut deployment.contracts {
+                contract.address = REDACTED_ADDRESS.to_string();
+            }
+        }
+    }
+
+    /// Leaves out the networks, deployed or missing, of the ecosystems (see
+    /// [`split_network_name`]) named in `prefixes`, ignoring case.
+    pub fn exclude_ecosystems(&mut self, prefixes: &[String]) {
+        let is_excluded = |network: &str| {
+            let (prefix, _) = split_network_name(network);
+            prefixes.iter().any(|excluded| excluded.eq_ignore_ascii_case(&prefix))
+        };
+        self.deployments.retain(|deployment| !is_excluded(&deployment.network));
+        self.missing.retain(|missing| !is_excluded(&missing.network));
+    }
+
+    /// The (network, contract, address) of every contract whose address is
+    /// malformed, see [`is_malformed_address`].
+    pub fn malformed_addresses(&self) -> Vec<(String, String, String)> {
+        self.deployments.iter()
+            .flat_map(|deployment| deployment.contracts.iter().map(move |contract| (deployment, contract)))
+            .filter(|(_, contract)| is_malformed_address(&contract.address))
+            .map(|(deployment, contract)| (deployment.network.clone(), contract.display_name(), contract.address.clone()))
+            .collect()
+    }
+
+    /// The deployments older than `max_age`, as (network, age in days).
+    pub fn old_deployments(&self, ctx: &Context, max_age: Duration) -> Result<Vec<(String, u64)>, String> {
+        let old = find_deployments_older_than(ctx, max_age)?;
+        Ok(self.deployments.iter()
+            .filter_map(|deployment| old.iter()
+                .find(|old| old.chain_id == deployment.chain_id)
+                .map(|old| (deployment.network.clone(), old.age_days)))
+            .collect())
+    }
+
+    /// Why each missing network has no deployment, by network name.
+    pub fn missing_reasons(&self, ctx: &Context) -> Result<HashMap<String, String>, String> {
+        self.missing.iter()
+            .map(|missing| Ok((missing.network.clone(), explain_missing_deployment(ctx, missing.chain_id)?.description(missing.chain_id))))
+            .collect()
+    }
+
+    /// One flat JSON record per contract, plus one per missing network when
+    /// `include_missing` is set, keeping the keys `fields` selects (all of them
+    /// when it's empty).
+    pub fn json_records(&self, include_missing: bool, fields: &[String]) -> Vec<Value> {
+        let has_field = |field: &str| fields.is_empty() || fields.iter().any(|selected| selected == field);
+        let mut records: Vec<Value> = Vec::new();
+        for deployment in &self.deployments {
+            for contract in &deployment.contracts {
+                let mut record = serde_json::json!({
+                    "network": deployment.network,
+                    "contract": contract.name,
+                    "address": contract.address,
+                    "chainId": deployment.chain_id,
+                });
+                if contract.external {
+                    record["external"] = Value::Bool(true);
+                }
+                if let Some(alias) = &contract.alias {
+                    record["alias"] = Value::String(alias.clone());
+                }
+                if let Some(commit) = &deployment.commit {
+                    record["commit"] = Value::String(commit.clone());
+                }
+                records.push(select_record_fields(record, &has_field));
+            }
+        }
+        if include_missing {
+            for missing in &self.missing {
+                records.push(select_record_fields(serde_json::json!({
+                    "network": missing.network,
+                    "contract": null,
+                    "address": null,
+                    "chainId": missing.chain_id,
+                }), &has_field));
+            }
+        }
+        records
+    }
+
+    /// The address of each contract by contract (display) name and network.
+    pub fn contracts_by_name(self) -> BTreeMap<String, BTreeMap<String, String>> {
+        let mut by_contract: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+        for deployment in self.deployments {
+            for contract in deployment.contracts {
+                by_contract.entry(contract.display_name()).or_default().insert(deployment.network.clone(), contract.address);
+            }
+        }
+        by_contract
+    }
+
+    /// Fills in the unmatched contract names and sorts the report by network.
+    fn finish(&mut self, selection: ContractSelection, matched_filters: &HashSet<String>) {
+        if let ContractSelection::Matching(names) = selection {
+            self.unmatched_contracts = names.iter()
+                .filter(|name| !matched_filters.contains(*name))
+                .cloned()
+                .collect();
+        }
+
+        self.deployments.sort_by(|a, b| a.network.cmp(&b.network));
+        self.missing.sort_by(|a, b| a.network.cmp(&b.network));
+    }
+
+    /// Adds the contracts of an aliases file as external contracts. A configured
+    /// network without a deployment gets one holding just its aliases; aliases
+    /// for networks that aren't configured are reported as warnings.
+    pub fn add_aliases(&mut self, aliases: &Aliases) {
+        for (network, contracts) in aliases {
+            let external = contracts.iter().map(|(name, address)| DeployedContract {
+                name: name.clone(),
+                address: address.clone(),
+                external: true,
+                tags: Vec::new(),
+                alias: None,
+            });
+
+            if let Some(deployment) = self.deployments.iter_mut().find(|d| &d.network == network) {
+                deployment.contracts.extend(external);
+            } else if let Some(index) = self.missing.iter().position(|m| &m.network == network) {
+                let missing = self.missing.remove(index);
+                self.deployments.push(NetworkDeployment {
+                    network: missing.network,
+                    chain_id: missing.chain_id,
+                    contracts: external.collect(),
+                    commit: None,
+                });
+            } else {
+                self.warnings.push(format!("Aliases given for unconfigured network {}", network));
+            }
+        }
+
+        self.deployments.sort_by(|a, b| a.network.cmp(&b.network));
+    }
+
+    /// Gives the contracts their friendly names from `aliases`. The raw keys
+    /// are kept in `name`.
+    pub fn apply_alias_map(&mut self, aliases: &ContractAliasMap) {
+        for deployment in &mut self.deployments {
+            for contract in &mut deployment.contracts {
+                if let Some(alias) = aliases.get(&deployment.network, &contract.name) {
+                    contract.alias = Some(alias.to_string());
+                }
+            }
+        }
+    }
+}
+
+/// Keeps the keys of a [`ListReport::json_records`] record that `has_field`
+/// selects.
+fn select_record_fields(mut record: Value, has_field: &dyn Fn(&str) -> bool) -> Value {
+    if let Some(record) = record.as_object_mut() {
+        record.retain(|key, _| match key.as_str() {
+            "chainId" => has_field("chainid"),
+            "external" | "alias" => has_field("contract"),
+            // Only there with --since-commit, which asked for it
+            "commit" => true,
+            key => has_field(key),
+        });
+    }
+    record
+}
+
+/// Removes the keys `fields` doesn't select from the deployments and missing
+/// networks of a serialized [`ListReport`]. An empty `fields` keeps them all.
+pub fn select_report_fields(report: &mut Value, fields: &[String]) {
+    if fields.is_empty() {
+        return;
+    }
+    let has_field = |field: &str| fields.iter().any(|selected| selected == field);
+    for deployment in report["deployments"].as_array_mut().into_iter().flatten() {
+        let deployment = deployment.as_object_mut().expect("deployments serialize as objects");
+        if !has_field("network") {
+            deployment.remove("network");
+        }
+        if !has_field("chainid") {
+            deployment.remove("chain_id");
+        }
+        if !has_field("contract") && !has_field("address") {
+            deployment.remove("contracts");
+        }
+        for contract in deployment.get_mut("contracts").and_then(Value::as_array_mut).into_iter().flatten() {
+            let contract = contract.as_object_mut().expect("contracts serialize as objects");
+            if !has_field("contract") {
+                contract.remove("name");
+                contract.remove("external");
+            }
+            if !has_field("address") {
+                contract.remove("address");
+            }
+        }
+    }
+    for missing in report["missing"].as_array_mut().into_iter().flatten() {
+        let missing = missing.as_object_mut().expect("missing networks serialize as objects");
+        if !has_field("network") {
+            missing.remove("network");
+        }
+        if !has_field("chainid") {
+            missing.remove("chain_id");
+        }
+    }
+}
+
+/// Merges the contracts deployed under the same name and address (ignoring
+/// case) on several networks, as (contract, address, networks) in order of
+/// first appearance.
+pub fn collapse_identical_contracts(deployments: &[NetworkDeployment]) -> Vec<(String, String, Vec<String>)> {
+    let mut rows: Vec<(String, String, Vec<String>)> = Vec::new();
+    for deployment in deployments {
+        for contract in &deployment.contracts {
+            let name = contract.display_name();
+            match rows.iter_mut().find(|(row_name, address, _)| *row_name == name && address.eq_ignore_ascii_case(&contract.address)) {
+                Some((_, _, networks)) => networks.push(deployment.network.clone()),
+                None => rows.push((name, contract.address.clone(), vec![deployment.network.clone()])),
+            }
+        }
+    }
+    rows
+}
+
+/// Friendly display names for raw contract keys, see [`load_alias_map`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContractAliasMap {
+    /// Names used on every network
+    pub global: BTreeMap<String, String>,
+    /// Names for a single network, which take precedence over the global ones
+    pub networks: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl ContractAliasMap {
+    /// The friendly name of the contract `key` on `network`, if it has one.
+    pub fn get(&self, network: &str, key: &str) -> Option<&str> {
+        self.networks.get(network)
+            .and_then(|names| names.get(key))
+            .or_else(|| self.global.get(key))
+            .map(String::as_str)
+    }
+}
+
+/// Reads a TOML alias map. Top-level keys map contract keys to display names
+/// on every network, and a table per network overrides them there:
+///
+/// ```toml
+/// "MyModuleV2Final#TokenProxy" = "Token"
+///
+/// [base]
+/// "MyModuleV2Final#TokenProxy" = "Token (Base)"
+/// ```
+pub fn load_alias_map(path: &Path) -> Result<ContractAliasMap, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read alias map {}: {}", path.display(), e))?;
+    let table: toml::Table = toml::from_str(strip_bom(&content))
+        .map_err(|e| format!("Failed to parse alias map {}: {}", path.display(), e))?;
+
+    let invalid = |key: &str| format!("Invalid alias map {}: {} must be a display name or a table of them", path.display(), key);
+    let mut aliases = ContractAliasMap::default();
+    for (key, value) in table {
+        match value {
+            toml::Value::String(alias) => {
+                aliases.global.insert(key, alias);
+            }
+            toml::Value::Table(names) => {
+                let mut network = BTreeMap::new();
+                for (contract, alias) in names {
+                    let toml::Value::String(alias) = alias else {
+                        return Err(invalid(&format!("{}.{}", key, contract)));
+                    };
+                    network.insert(contract, alias);
+                }
+                aliases.networks.insert(key, network);
+            }
+            _ => return Err(invalid(&key)),
+        }
+    }
+    Ok(aliases)
+}
+
+/// Operational tags (e.g. `audited`, `owner:multisig`) by network and contract.
+pub type DeploymentTags = BTreeMap<String, BTreeMap<String, Vec<String>>>;
+
+/// Reads the project's `deployments-meta.toml`, which has a table per network
+/// mapping contracts to their tags:
+///
+/// ```toml
+/// [base]
+/// "TokenModule#Token" = ["audited", "owner:multisig"]
+/// ```
+///
+/// Returns no tags when the file doesn't exist.
+pub fn load_deployment_tags(ctx: &Context) -> Result<DeploymentTags, String> {
+    let Some(content) = ctx.read_file(&ctx.root.join("deployments-meta.toml"))? else {
+        return Ok(DeploymentTags::new());
+    };
+    toml::from_str(strip_bom(&content))
+        .map_err(|e| format!("Failed to parse deployments-meta.toml: {}", e))
+}
+
+/// Reads a list of contract names from `path`: either one name per line
+/// (blank lines and lines starting with `#` are skipped, since `#` is also
+/// part of Ignition keys) or a TOML array, bare or as
+/// `contracts = [...]`.
+pub fn load_contract_names(path: &Path) -> Result<Vec<String>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read contracts file {}: {}", path.display(), e))?;
+    let content = strip_bom(&content).trim();
+
+    #[derive(Deserialize)]
+    struct ContractsFile {
+        contracts: Vec<String>,
+    }
+    if content.starts_with('[') {
+        return toml::from_str::<ContractsFile>(&format!("contracts = {}", content))
+            .map(|file| file.contracts)
+            .map_err(|e| format!("Failed to parse contracts file {}: {}", path.display(), e));
+    }
+    if content.starts_with("contracts") && content.contains('=') {
+        return toml::from_str::<ContractsFile>(content)
+            .map(|file| file.contracts)
+            .map_err(|e| format!("Failed to parse contracts file {}: {}", path.display(), e));
+    }
+
+    Ok(content.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
+
+/// External contract addresses by network and contract name.
+pub type Aliases = BTreeMap<String, BTreeMap<String, String>>;
+
+/// Reads an aliases file: a JSON object mapping network names to objects of
+/// contract names and addresses, e.g. `{"base": {"Multicall3": "0x..."}}`.
+pub fn load_aliases(path: &Path) -> Result<Aliases, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read aliases file {}: {}", path.display(), e))?;
+    serde_json::from_str(strip_bom(&content))
+        .map_err(|e| format!("Failed to parse aliases file {}: {}", path.display(), e))
+}
+
+/// What `list` reports on: where the deployments are read from, which of
+/// their contracts and networks are kept, and how they're named. See
+/// [`build_selected_report`].
+#[derive(Default)]
+pub struct ListSelection<'a> {
+    /// Contracts to list by name, see [`ContractSelection::Matching`]
+    pub contracts: &'a [String],
+    /// Regex picking each network's primary contract
+    pub primary_contract: Option<&'a str>,
+    /// Regex of the contracts to list
+    pub contract_pattern: Option<&'a str>,
+    /// Every contract of each deployment, unless contracts are selected by
+    /// name or pattern
+    pub all_contracts: bool,
+    /// Networks to keep, all of them when empty
+    pub networks: &'a [String],
+    /// Ecosystems to leave out, see [`ListReport::exclude_ecosystems`]
+    pub exclude_prefixes: &'a [String],
+    pub git_ref: Option<&'a str>,
+    pub since_commit: Option<&'a str>,
+    /// Aliases file of external contracts, see [`load_aliases`]
+    pub aliases: Option<&'a Path>,
+    /// Friendly contract names, see [`load_alias_map`]
+    pub alias_map: Option<&'a Path>,
+    pub show_tags: bool,
+    pub sort_contracts: bool,
+    pub normalize_network_names: bool,
+    /// List the deployment directories without reading the hardhat config
+    pub deployments_only: bool,
+    /// External resolver of the addresses, see [`build_resolver_report`]
+    pub resolver_cmd: Option<&'a str>,
+    pub resolver_args: &'a [String],
+    /// Fail, rather than warn, when no networks could be parsed from the config
+    pub strict_config: bool,
+}
+
+impl ListSelection<'_> {
+    /// Whether the listed contracts are named, rather than each network
+    /// showing one address.
+    pub fn shows_contracts(&self) -> bool {
+        !self.contracts.is_empty() || self.contract_pattern.is_some() || self.aliases.is_some()
+    }
+
+    /// How the networks without a deployment are described.
+    pub fn missing_description(&self) -> &'static str {
+        if self.deployments_only {
+            "with a deployment directory but no deployed addresses"
+        } else {
+            "in hardhat config without corresponding deployment(s)"
+        }
+    }
+}
+
+/// Builds the list report `selection` asks for: reads the deployments (from
+/// the deployment directories, a resolver or the config's networks), then
+/// adds aliases and tags, filters the networks and names the contracts.
+pub fn build_selected_report(ctx: &Context, selection: &ListSelection) -> Result<ListReport, String> {
+    let contract_pattern = selection.contract_pattern
+        .map(Regex::new)
+        .transpose()
+        .map_err(|e| format!("Invalid --only-contracts-matching pattern: {}", e))?;
+    let primary_pattern = selection.primary_contract
+        .map(Regex::new)
+        .transpose()
+        .map_err(|e| format!("Invalid --primary-contract pattern: {}", e))?;
+    let contracts = if !selection.contracts.is_empty() {
+        ContractSelection::Matching(selection.contracts)
+    } else if let Some(pattern) = &contract_pattern {
+        ContractSelection::Pattern(pattern)
+    } else if selection.all_contracts {
+        ContractSelection::All
+    } else if let Some(primary) = &primary_pattern {
+        ContractSelection::Primary(primary)
+    } else {
+        ContractSelection::First
+    };
+    let mut report = if selection.deployments_only {
+        build_deployment_scan_report(ctx, contracts)?
+    } else if let Some(program) = selection.resolver_cmd {
+        build_resolver_report(ctx, program, selection.resolver_args)?
+    } else {
+        build_list_report_at(ctx, contracts, selection.git_ref)?
+    };
+    if report.configured_networks == 0 && !selection.deployments_only {
+        let message = format!("No networks with a chainId could be parsed from {}, so there is nothing to list. \
+            Networks are expected in the form `name: {{ chainId: 8453 }}` or `{{ name: \"name\", chainId: 8453 }}`", ctx.config_name());
+        if selection.strict_config {
+            return Err(message);
+        }
+        report.warnings.insert(0, message);
+    }
+    if let Some(path) = selection.aliases {
+        report.add_aliases(&load_aliases(path)?);
+    }
+    if let Some(since) = selection.since_commit {
+        report.retain_changed_since(ctx, since)?;
+    }
+    if !selection.networks.is_empty() {
+        report.retain_networks(selection.networks);
+    }
+    report.exclude_ecosystems(selection.exclude_prefixes);
+    if selection.normalize_network_names {
+        report.normalize_network_names();
+    }
+    if selection.show_tags {
+        report.add_tags(&load_deployment_tags(ctx)?);
+    }
+    if let Some(path) = selection.alias_map {
+        report.apply_alias_map(&load_alias_map(path)?);
+    }
+    if selection.sort_contracts {
+        report.sort_contracts();
+    }
+    Ok(report)
+}
+
+/// Looks up the deployment of every configured network (except `hardhat`).
+pub fn build_list_report(ctx: &Context, selection: ContractSelection) -> Result<ListReport, String> {
+    build_list_report_at(ctx, selection, None)
+}
+
+/// Like [`build_list_report`], but reads the `deployed_addresses.json` files
+/// as they were at `git_ref` (if given) instead of from the working tree. The
+/// networks still come from the current config.
+pub fn build_list_report_at(ctx: &Context, selection: ContractSelection, git_ref: Option<&str>) -> Result<ListReport, String> {
+    match git_ref {
+        Some(git_ref) => {
+            ctx.require_working_tree("--git-ref")?;
+            git::verify_ref(&ctx.root, git_ref)?;
+            build_list_report_from(ctx, selection, &GitRef { root: &ctx.root, git_ref })
+        }
+        None => {
+            deployments_dir(ctx)?;
+            build_list_report_from(ctx, selection, ctx.source.as_ref())
+        }
+    }
+}
+
+/// Like [`build_list_report`], but reads the `deployed_addresses.json` files
+/// from `source`, e.g. an archived snapshot of the project. Networks whose
+/// directory is ignored by `ctx` are left out.
+pub fn build_list_report_from(ctx: &Context, selection: ContractSelection, source: &dyn DeploymentSource) -> Result<ListReport, String> {
+    let networks = parse_hardhat_config(ctx)?;
+    let mut report = ListReport {
+        default_network: parse_default_network(ctx)?.filter(|network| network != "hardhat"),
+        configured_networks: networks.keys().filter(|network| *network != "hardhat").count(),
+        ..ListReport::default()
+    };
+    let mut matched_filters = HashSet::new();
+
+    for (network_name, chain_id) in networks {
+        if network_name == "hardhat" || ctx.is_ignored_dir_name(&format!("chain-{}", chain_id)) {
+            continue;
+        }
+
+        let deployed = ctx.timings.time("directory scanning", || {
+            source.read_file(&format!("deployments/chain-{}/deployed_addresses.json", chain_id))
+                .and_then(|content| content.map_or(Ok(None), |content| {
+                    for (contract, _) in find_padded_entries(&content)? {
+                        report.warnings.push(format!("Address of {} on {} has surrounding whitespace", contract, network_name));
+                    }
+                    parse_deployed_addresses(&content)
+                }))
+        });
+
+        let selected = deployed.and_then(|deployed| {
+            deployed.map(|deployed| select_contracts(deployed, selection, &mut matched_filters)).transpose()
+        });
+        match selected {
+            Ok(Some(selected)) => report.push_deployment(network_name, chain_id, selected),
+            Ok(None) => {
+                report.missing.push(MissingNetwork { network: network_name, chain_id });
+            }
+            Err(e) => report.errors.push(ctx.errors.network_error(chain_id, format!("Error reading deployment for {}: {}", network_name, e))?),
+        }
+    }
+
+    report.finish(selection, &matched_filters);
+    Ok(report)
+}
+
+/// Like [`build_list_report`], but without reading the hardhat config: every
+/// `chain-<id>` directory is listed, named after its chain in the bundled
+/// registry (or `chain-<id>` for chains the registry doesn't know).
+pub fn build_deployment_scan_report(ctx: &Context, selection: ContractSelection) -> Result<ListReport, String> {
+    let mut report = ListReport::default();
+    let mut matched_filters = HashSet::new();
+
+    for (chain_id, dir) in chain_deployment_dirs(ctx)? {
+        let network_name = ctx.chain_name(chain_id)
+            .map_or_else(|| format!("chain-{}", chain_id), str::to_string);
+        let selected = ctx.timings.time("directory scanning", || get_deployment_contracts(ctx, &dir))
+            .and_then(|deployed| deployed.map(|deployed| select_contracts(deployed, selection, &mut matched_filters)).transpose());
+        match selected {
+            Ok(Some(selected)) => report.push_deployment(network_name, chain_id, selected),
+            Ok(None) => report.missing.push(MissingNetwork { network: network_name, chain_id }),
+            Err(e) => report.errors.push(ctx.errors.network_error(chain_id, format!("Error reading deployment for {}: {}", network_name, e))?),
+        }
+    }
+
+    report.finish(selection, &matched_filters);
+    Ok(report)
+}
+
+/// Like [`build_list_report`], but the address of each network comes from an
+/// external resolver instead of the deployments directory. `program` is run
+/// directly (not through a shell) with `args` followed by the deployments
+/// directory, and must print a JSON object mapping network names to
+/// addresses. Resolved addresses have no contract name.
+pub fn build_resolver_report(ctx: &Context, program: &str, args: &[String]) -> Result<ListReport, String> {
+    ctx.require_working_tree("--resolver-cmd")?;
+    let networks = parse_hardhat_config(ctx)?;
+    let deployments_dir = deployments_dir(ctx)?;
+    if program.is_empty() {
+        return Err("The resolver command is empty".to_string());
+    }
+
+    let output = std::process::Command::new(program)
+        .args(args)
+        .arg(&deployments_dir)
+        .output()
+        .map_err(|e| format!("Failed to run resolver command {}: {}", program, e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let mut message = format!("Resolver command {} failed ({})", program, output.status);
+        if !stderr.trim().is_empty() {
+            message.push_str(&format!(": {}", stderr.trim()));
+        }
+        return Err(message);
+    }
+
+    let resolved: Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse resolver output as JSON: {}", e))?;
+    let resolved = resolved.as_object()
+        .ok_or("Resolver output must be a JSON object mapping network names to addresses")?;
+    let mut addresses = HashMap::new();
+    for (network, address) in resolved {
+        match address.as_str() {
+            Some(address) if is_valid_address(address) => {
+                addresses.insert(network.as_str(), address.to_string());
+            }
+            _ => return Err(format!("Resolver output maps network {} to {}, which isn't an address", network, address)),
+        }
+    }
+
+    let mut report = ListReport {
+        default_network: parse_default_network(ctx)?.filter(|network| network != "hardhat"),
+        configured_networks: networks.keys().filter(|network| *network != "hardhat").count(),
+        ..ListReport::default()
+    };
+    for (network, chain_id) in &networks {
+        if network == "hardhat" {
+            continue;
+        }
+        match addresses.remove(network.as_str()) {
+            Some(address) => report.push_deployment(network.clone(), *chain_id, vec![(String::new(), address)]),
+            None => report.missing.push(MissingNetwork { network: network.clone(), chain_id: *chain_id }),
+        }
+    }
+    let mut unconfigured: Vec<&str> = addresses.into_keys().collect();
+    unconfigured.sort();
+    for network in unconfigured {
+        report.warnings.push(format!("Resolver returned an address for unconfigured network {}", network));
+    }
+
+    report.finish(ContractSelection::First, &HashSet::new());
+    Ok(report)
+}
+
+/// Picks the contracts of a deployment a list report should include, recording
+/// which of the [`ContractSelection::Matching`] names matched something. A
+/// primary contract pattern matching several contracts is an error, so the
+/// pick never depends on the order of `deployed_addresses.json`.
+fn select_contracts(deployed: ContractEntries, selection: ContractSelection, matched_filters: &mut HashSet<String>) -> Result<ContractEntries, String> {
+    Ok(match selection {
+        ContractSelection::First => deployed.into_iter().take(1).collect(),
+        ContractSelection::Primary(pattern) => {
+            let matches: Vec<&(String, String)> = deployed.iter().filter(|(key, _)| key_matches_pattern(key, pattern)).collect();
+            match matches[..] {
+                [] => deployed.into_iter().take(1).collect(),
+                [primary] => vec![primary.clone()],
+                _ => return Err(format!(
+                    "Primary contract pattern {} matches {}",
+                    pattern,
+                    matches.iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>().join(", ")
+                )),
+            }
+        }
+        ContractSelection::All => deployed,
+        ContractSelection::Matching(names) => {
+            let mut selected = Vec::new();
+            for (key, address) in deployed {
+                if let Some(name) = names.iter().find(|name| contract_matches(&key, name)) {
+                    matched_filters.insert(name.clone());
+                    selected.push((key, address));
+                }
+            }
+            selected
+        }
+        ContractSelection::Pattern(pattern) => deployed.into_iter()
+            .filter(|(key, _)| key_matches_pattern(key, pattern))
+            .collect(),
+    })
+}
+
+/// Whether the full `Module#Contract` key or the contract name matches the
+/// regex.
+fn key_matches_pattern(key: &str, pattern: &Regex) -> bool {
+    pattern.is_match(key) || pattern.is_match(split_contract_key(key).1)
+}
+
+/// How a contract's address on a network changed between two git refs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AddressChangeKind {
+    Added,
+    Removed,
+    Changed,
+    Unchanged,
+}
+
+impl AddressChangeKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AddressChangeKind::Added => "added",
+            AddressChangeKind::Removed => "removed",
+            AddressChangeKind::Changed => "changed",
+            AddressChangeKind::Unchanged => "unchanged",
+        }
+    }
+}
+
+/// How a network differs between two hardhat configs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NetworkChangeKind {
+    Added,
+    Removed,
+    ChainIdChanged,
+}
+
+impl NetworkChangeKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NetworkChangeKind::Added => "added",
+            NetworkChangeKind::Removed => "removed",
+            NetworkChangeKind::ChainIdChanged => "chain-id-changed",
+        }
+    }
+}
+
+/// A network that was added, removed or given another chain ID between two
+/// hardhat configs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigNetworkChange {
+    pub network: String,
+    /// Chain ID in the other config, if it declares the network
+    pub from: Option<u64>,
+    /// Chain ID in the project's config, if it declares the network
+    pub to: Option<u64>,
+    pub change: NetworkChangeKind,
+}
+
+/// Compares the networks declared in the project's hardhat config against the
+/// config at `other`, which is treated as the older one: networks only in the
+/// project's config are added, networks only in `other` are removed.
+/// Deployments and `network-config.json` are not looked at. Sorted by network
+/// name.
+pub fn compare_config_networks(ctx: &Context, other: &Path) -> Result<Vec<ConfigNetworkChange>, String> {
+    let other_content = fs::read_to_string(other)
+        .map_err(|e| format!("Failed to read {}: {}", other.display(), e))?;
+    let before: BTreeMap<String, u64> = parse_config_networks(&other_content)?.into_iter().collect();
+    let after: BTreeMap<String, u64> = parse_config_networks(&ctx.read_config()?)?.into_iter().collect();
+
+    let mut changes = Vec::new();
+    for (network, chain_id) in &after {
+        let change = match before.get(network) {
+            None => NetworkChangeKind::Added,
+            Some(previous) if previous != chain_id => NetworkChangeKind::ChainIdChanged,
+            Some(_) => continue,
+        };
+        changes.push(ConfigNetworkChange { network: network.clone(), from: before.get(network).copied(), to: Some(*chain_id), change });
+    }
+    for (network, chain_id) in &before {
+        if !after.contains_key(network) {
+            changes.push(ConfigNetworkChange { network: network.clone(), from: Some(*chain_id), to: None, change: NetworkChangeKind::Removed });
+        }
+    }
+    changes.sort_by(|a, b| a.network.cmp(&b.network));
+
+    Ok(changes)
+}
+
+/// A contract's address on one network at two git refs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContractAddressChange {
+    pub network: String,
+    pub chain_id: u64,
+    /// Address at the older ref, if the contract was deployed there
+    pub from: Option<String>,
+    /// Address at the newer ref, if the contract was deployed there
+    pub to: Option<String>,
+    pub change: AddressChangeKind,
+}
+
+/// Compares the address of `contract` on every configured network between the
+/// `from` and `to` git refs. Networks the contract isn't deployed to at either
+/// ref are left out.
+pub fn diff_contract_addresses(ctx: &Context, contract: &str, from: &str, to: &str) -> Result<Vec<ContractAddressChange>, String> {
+    ctx.require_working_tree("diff-contract")?;
+    let names = [contract.to_string()];
+    let addresses_at = |git_ref: &str| -> Result<BTreeMap<String, (u64, String)>, String> {
+        let report = build_list_report_at(ctx, ContractSelection::Matching(&names), Some(git_ref))?;
+        Ok(report.deployments.into_iter()
+            .filter_map(|deployment| {
+                let address = deployment.contracts.into_iter().next()?.address;
+                Some((deployment.network, (deployment.chain_id, address)))
+            })
+            .collect())
+    };
+    let mut before = addresses_at(from)?;
+    let after = addresses_at(to)?;
+
+    let mut changes = Vec::new();
+    for (network, (chain_id, to_address)) in after {
+        let from_address = before.remove(&network).map(|(_, address)| address);
+        let change = match &from_address {
+            None => AddressChangeKind::Added,
+            Some(address) if address.eq_ignore_ascii_case(&to_address) => AddressChangeKind::Unchanged,
+            Some(_) => AddressChangeKind::Changed,
+        };
+        changes.push(ContractAddressChange { network, chain_id, from: from_address, to: Some(to_address), change });
+    }
+    for (network, (chain_id, from_address)) in before {
+        changes.push(ContractAddressChange {
+            network,
+            chain_id,
+            from: Some(from_address),
+            to: None,
+            change: AddressChangeKind::Removed,
+        });
+    }
+
+    changes.sort_by(|a, b| a.network.cmp(&b.network));
+    Ok(changes)
+}
+
+/// Network names that normalize to the same name.
+#[derive(Debug, Clone, Serialize)]
+pub struct SimilarNetworkNames {
+    /// The shared lowercased name without whitespace
+    pub normalized: String,
+    pub networks: Vec<String>,
+}
+
+/// A `chain-<id>` directory no configured network points at.
+#[derive(Debug, Clone, Serialize)]
+pub struct UnconfiguredDeployment {
+    pub chain_id: u64,
+    /// Name of the chain in the bundled registry (or the overrides), if it's
+    /// a known chain
+    pub probable_network: Option<String>,
+}
+
+/// Mismatches between the networks in the config and the deployments
+/// directory.
+#[derive(Debug, Default, Serialize)]
+pub struct AuditReport {
+    pub config_without_deployment: Vec<MissingNetwork>,
+    pub deployment_without_config: Vec<UnconfiguredDeployment>,
+    pub similar_network_names: Vec<SimilarNetworkNames>,
+    pub chain_id_mismatches: Vec<ChainIdMismatch>,
+    /// Deployments below the `forbid_chains_below` floor, if one was given
+    pub chains_below_floor: Vec<u64>,
+    pub checksum_inconsistencies: Vec<ChecksumInconsistency>,
+    /// Deployments older than the `max_age` threshold, if one was given
+    pub old_deployments: Vec<OldDeployment>,
+    /// Chains in the expected chains list, if one was given, that have no
+    /// deployment
+    pub expected_without_deployment: Vec<u64>,
+    /// Deployments on chains missing from the expected chains list, if one
+    /// was given
+    pub deployment_not_expected: Vec<u64>,
+    /// Function selectors shared across contracts of a deployment, if
+    /// selectors were checked
+    pub selector_collisions: Vec<SelectorCollision>,
+    pub padded_addresses: Vec<PaddedAddress>,
+    pub malformed_addresses: Vec<MalformedAddress>,
+}
+
+/// Number of findings in each [`AuditReport`] category.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuditSummary {
+    pub config_without_deployment: usize,
+    pub deployment_without_config: usize,
+    pub similar_network_names: usize,
+    pub chain_id_mismatches: usize,
+    pub chains_below_floor: usize,
+    pub checksum_inconsistencies: usize,
+    pub old_deployments: usize,
+    pub expected_without_deployment: usize,
+    pub deployment_not_expected: usize,
+    pub selector_collisions: usize,
+    pub padded_addresses: usize,
+    pub malformed_addresses: usize,
+}
+
+impl AuditReport {
+    pub fn summary(&self) -> AuditSummary {
+        AuditSummary {
+            config_without_deployment: self.config_without_deployment.len(),
+            deployment_without_config: self.deployment_without_config.len(),
+            similar_network_names: self.similar_network_names.len(),
+            chain_id_mismatches: self.chain_id_mismatches.len(),
+            chains_below_floor: self.chains_below_floor.len(),
+            checksum_inconsistencies: self.checksum_inconsistencies.len(),
+            old_deployments: self.old_deployments.len(),
+            expected_without_deployment: self.expected_without_deployment.len(),
+            deployment_not_expected: self.deployment_not_expected.len(),
+            selector_collisions: self.selector_collisions.len(),
+            padded_addresses: self.padded_addresses.len(),
+            malformed_addresses: self.malformed_addresses.len(),
+        }
+    }
+}
+
+/// Audits the config against the deployments directory. When
+/// `forbid_chains_below` is set, deployments on lower chain IDs are reported
+/// too, when `max_age` is set, deployments older than it, when
+/// `expected_chains` is set, the differences between it and the deployments,
+/// and when `check_selectors` is set, function selector collisions.
+pub fn build_audit_report(ctx: &Context, forbid_chains_below: Option<u64>, max_age: Option<Duration>, expected_chains: Option<&[u64]>, check_selectors: bool) -> Result<AuditReport, String> {
+    let networks = parse_hardhat_config(ctx)?;
+
+    let mut config_without_deployment = find_configs_without_deployment(ctx, &networks)?;
+    config_without_deployment.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut deployment_without_config = find_deployments_without_config(ctx, &networks)?;
+    deployment_without_config.sort();
+
+    let (expected_without_deployment, deployment_not_expected) = match expected_chains {
+        Some(expected) => compare_expected_chains(ctx, expected)?,
+        None => (Vec::new(), Vec::new()),
+    };
+
+    Ok(AuditReport {
+        config_without_deployment: config_without_deployment.into_iter()
+            .map(|(network, chain_id)| MissingNetwork { network, chain_id })
+            .collect(),
+        deployment_without_config: deployment_without_config.into_iter()
+            .map(|chain_id| UnconfiguredDeployment { chain_id, probable_network: ctx.chain_name(chain_id).map(str::to_string) })
+            .collect(),
+        similar_network_names: find_similar_network_names(&networks),
+        chain_id_mismatches: find_chain_id_mismatches(ctx)?,
+        chains_below_floor: match forbid_chains_below {
+            Some(floor) => find_deployments_below_chain_id(ctx, floor)?,
+            None => Vec::new(),
+        },
+        checksum_inconsistencies: find_checksum_inconsistencies(ctx)?,
+        old_deployments: match max_age {
+            Some(max_age) => find_deployments_older_than(ctx, max_age)?,
+            None => Vec::new(),
+        },
+        expected_without_deployment,
+        deployment_not_expected,
+        selector_collisions: if check_selectors { find_selector_collisions(ctx)? } else { Vec::new() },
+        padded_addresses: find_padded_addresses(ctx)?,
+        malformed_addresses: find_malformed_addresses(ctx)?,
+    })
+}
+
+/// Compares the deployments against a list of chains they are expected on,
+/// independent of the config. Returns the expected chain IDs without a
+/// deployment and the chain IDs deployed to but not expected, both sorted.
+pub fn compare_expected_chains(ctx: &Context, expected: &[u64]) -> Result<(Vec<u64>, Vec<u64>), String> {
+    let deployed = chain_deployment_dirs(ctx)?;
+
+    let mut expected: Vec<u64> = expected.iter()
+        .copied()
+        .filter(|chain_id| ctx.is_selected_chain_dir(&format!("chain-{}", chain_id)))
+        .collect();
+    expected.sort();
+    expected.dedup();
+
+    let mut without_deployment = Vec::new();
+    for &chain_id in &expected {
+        let Some((_, dir)) = deployed.iter().find(|(id, _)| *id == chain_id) else {
+            without_deployment.push(chain_id);
+            continue;
+        };
+        match get_deployment_address(ctx, dir) {
+            Ok(Some(_)) => {}
+            Ok(None) => without_deployment.push(chain_id),
+            Err(e) => {
+                ctx.errors.network_error(chain_id, format!("Error reading deployment in chain-{}: {}", chain_id, e))?;
+            }
+        }
+    }
+
+    let not_expected = deployed.into_iter()
+        .map(|(chain_id, _)| chain_id)
+        .filter(|chain_id| !expected.contains(chain_id))
+        .collect();
+    Ok((without_deployment, not_expected))
+}
+
+/// Reads a list of chain IDs: one per line (lines starting with `#` are
+/// comments) or a JSON array, e.g. `[1, 8453]`.
+pub fn load_chain_ids(path: &Path) -> Result<Vec<u64>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read chains file {}: {}", path.display(), e))?;
+    let content = strip_bom(&content).trim();
+
+    if content.starts_with('[') {
+        return serde_json::from_str(content)
+            .map_err(|e| format!("Failed to parse chains file {}: {}", path.display(), e));
+    }
+
+    content.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.parse::<u64>()
+            .map_err(|_| format!("Invalid chain ID {} in chains file {}", line, path.display())))
+        .collect()
+}
+
+/// Finds configured networks that have no deployment, as (network, chain ID).
+pub fn find_configs_without_deployment(ctx: &Context, networks: &HashMap<String, u64>) -> Result<Vec<(String, u64)>, String> {
+    let deployments_dir = deployments_dir(ctx)?;
+    let mut config_without_deployment = Vec::new();
+
+    for (network_name, chain_id) in networks {
+        if network_name == "hardhat" || ctx.is_ignored_dir_name(&format!("chain-{}", chain_id)) {
+            continue;
+        }
+        let chain_dir = deployments_dir.join(format!("chain-{}", chain_id));
+        if ctx.list_dir(&chain_dir)?.is_none() {
+            config_without_deployment.push((network_name.clone(), *chain_id));
+            continue;
+        }
+        match get_deployment_address(ctx, &chain_dir) {
+            Ok(Some(_)) => {}
+            Ok(None) => config_without_deployment.push((network_name.clone(), *chain_id)),
+            Err(e) => {
+                ctx.errors.network_error(*chain_id, format!("Error reading deployment for {}: {}", network_name, e))?;
+            }
+        }
+    }
+
+    Ok(config_without_deployment)
+}
+
+/// Lists the `chain-<id>` directories inside `deployments/` as (chain ID,
+/// path), sorted by chain ID.
+pub fn chain_deployment_dirs(ctx: &Context) -> Result<Vec<(u64, PathBuf)>, String> {
+    ctx.timings.time("directory scanning", || {
+        let deployments_dir = deployments_dir(ctx)?;
+        let mut chain_dirs = Vec::new();
+
+        for (name, is_dir) in ctx.list_dir(&deployments_dir)?.unwrap_or_default() {
+            if !is_dir || ctx.is_ignored_dir_name(&name) {
+                continue;
+            }
+            let chain_id = name.strip_prefix("chain-")
+                .and_then(|id| id.parse::<u64>().ok());
+            if let Some(chain_id) = chain_id {
+                chain_dirs.push((chain_id, deployments_dir.join(name)));
+            }
+        }
+
+        chain_dirs.sort();
+        Ok(chain_dirs)
+    })
+}
+
+/// Finds the chain IDs of `chain-<id>` directories that no configured network
+/// points at.
+pub fn find_deployments_without_config(ctx: &Context, networks: &HashMap<String, u64>) -> Result<Vec<u64>, String> {
+    Ok(chain_deployment_dirs(ctx)?.into_iter()
+        .map(|(chain_id, _)| chain_id)
+        .filter(|chain_id| !networks.values().any(|id| id == chain_id))
+        .collect())
+}
+
+/// Finds the chain IDs of `chain-<id>` directories below `floor`, e.g. local
+/// hardhat (31337) deployments that were committed by accident.
+pub fn find_deployments_below_chain_id(ctx: &Context, floor: u64) -> Result<Vec<u64>, String> {
+    Ok(chain_deployment_dirs(ctx)?.into_iter()
+        .map(|(chain_id, _)| chain_id)
+        .filter(|&chain_id| chain_id < floor)
+        .collect())
+}
+
+/// Parses an age such as `90d`: a whole number followed by `s`, `m`, `h`, `d`
+/// or `w`.
+pub fn parse_age(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value.len() - value.chars().last().map_or(0, char::len_utf8);
+    let (number, unit) = value.split_at(split);
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(format!("Invalid age {}: expected a number followed by s, m, h, d or w (e.g. 90d)", value)),
+    };
+    let number: u64 = number.parse()
+        .map_err(|_| format!("Invalid age {}: expected a number followed by s, m, h, d or w (e.g. 90d)", value))?;
+    number.checked_mul(seconds)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("Age {} is too large", value))
+}
+
+/// A deployment older than the `max_age` threshold.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OldDeployment {
+    pub directory: String,
+    pub chain_id: u64,
+    /// Days since `deployed_addresses.json` was last modified
+    pub age_days: u64,
+}
+
+/// Finds deployments whose `deployed_addresses.json` was last modified more
+/// than `max_age` ago, oldest first.
+pub fn find_deployments_older_than(ctx: &Context, max_age: Duration) -> Result<Vec<OldDeployment>, String> {
+    let now = SystemTime::now();
+    let mut old = Vec::new();
+    for (chain_id, modified) in deployment_times(ctx)? {
+        let age = now.duration_since(modified).unwrap_or_default();
+        if age > max_age {
+            old.push(OldDeployment {
+                directory: format!("chain-{}", chain_id),
+                chain_id,
+                age_days: age.as_secs() / (24 * 60 * 60),
+            });
+        }
+    }
+    old.sort_by(|a, b| b.age_days.cmp(&a.age_days).then(a.chain_id.cmp(&b.chain_id)));
+    Ok(old)
+}
+
+/// When each deployment was last written, by chain ID, going by the
+/// modification time of its `deployed_addresses.json`. Deployments without
+/// one are left out.
+pub fn deployment_times(ctx: &Context) -> Result<BTreeMap<u64, SystemTime>, String> {
+    Ok(chain_deployment_dirs(ctx)?.into_iter()
+        .filter_map(|(chain_id, chain_dir)| {
+            ctx.modified(&chain_dir.join("deployed_addresses.json"))
+                .map(|modified| (chain_id, modified))
+        })
+        .collect())
+}
+
+/// Reads the chain ID a deployment records about itself, either as a
+/// top-level `chainId` in `deployed_addresses.json` or from the
+/// `DEPLOYMENT_INITIALIZE` entry that starts Ignition's `journal.jsonl`.
+pub fn recorded_chain_id(ctx: &Context, deployment_dir: &Path) -> Option<u64> {
+    let from_addresses = ctx.read_file(&deployment_dir.join("deployed_addresses.json")).ok().flatten()
+        .and_then(|content| serde_json::from_str::<Value>(strip_bom(&content)).ok())
+        .and_then(|data| data["chainId"].as_u64());
+
+    from_addresses.or_else(|| {
+        let journal = ctx.read_file(&deployment_dir.join("journal.jsonl")).ok()??;
+        let first: Value = serde_json::from_str(strip_bom(journal.lines().next()?)).ok()?;
+        first["chainId"].as_u64()
+    })
+}
+
+/// A `chain-<id>` directory whose deployment records a different chain ID.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChainIdMismatch {
+    pub directory: String,
+    pub directory_chain_id: u64,
+    pub recorded_chain_id: u64,
+}
+
+/// Finds `chain-<id>` directories whose recorded chain ID disagrees with the
+/// directory name, which usually means deployment files were copied between
+/// chains.
+pub fn find_chain_id_mismatches(ctx: &Context) -> Result<Vec<ChainIdMismatch>, String> {
+    let mut mismatches = Vec::new();
+
+    for (directory_chain_id, path) in chain_deployment_dirs(ctx)? {
+        if let Some(recorded_chain_id) = recorded_chain_id(ctx, &path) {
+            if recorded_chain_id != directory_chain_id {
+                mismatches.push(ChainIdMismatch {
+                    directory: format!("chain-{}", directory_chain_id),
+                    directory_chain_id,
+                    recorded_chain_id,
+                });
+            }
+        }
+    }
+
+    Ok(mismatches)
+}
+
+/// Calls `f` with the chain ID, directory and contracts of every deployment
+/// that has a `deployed_addresses.json`. Unreadable deployments go to
+/// [`ErrorLog::network_error`](errors::ErrorLog::network_error) and are
+/// skipped.
+fn for_each_deployment(ctx: &Context, mut f: impl FnMut(u64, &Path, ContractEntries)) -> Result<(), String> {
+    for (chain_id, path) in chain_deployment_dirs(ctx)? {
+        match ctx.timings.time("directory scanning", || get_deployment_contracts(ctx, &path)) {
+            Ok(Some(contracts)) => f(chain_id, &path, contracts),
+            Ok(None) => {}
+            Err(e) => {
+                ctx.errors.network_error(chain_id, format!("Error reading deployment in chain-{}: {}", chain_id, e))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A deployment whose addresses are partly EIP-55 checksummed and partly not,
+/// which usually means some of them were edited by hand.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChecksumInconsistency {
+    pub directory: String,
+    pub chain_id: u64,
+    /// Keys of the contracts whose address isn't checksummed
+    pub non_checksummed: Vec<String>,
+}
+
+/// Finds deployments mixing checksummed and non-checksummed addresses.
+/// Deployments that are consistently lowercase aren't reported, and unreadable
+/// ones go to [`ErrorLog::network_error`](errors::ErrorLog::network_error).
+pub fn find_checksum_inconsistencies(ctx: &Context) -> Result<Vec<ChecksumInconsistency>, String> {
+    let mut inconsistencies = Vec::new();
+
+    for_each_deployment(ctx, |chain_id, _, contracts| {
+        let (checksummed, non_checksummed): (Vec<_>, Vec<_>) = contracts.into_iter()
+            .filter(|(_, address)| is_valid_address(address))
+            .partition(|(_, address)| is_checksummed_address(address));
+        if !checksummed.is_empty() && !non_checksummed.is_empty() {
+            inconsistencies.push(ChecksumInconsistency {
+                directory: format!("chain-{}", chain_id),
+                chain_id,
+                non_checksummed: non_checksummed.into_iter().map(|(key, _)| key).collect(),
+            });
+        }
+    })?;
+
+    Ok(inconsistencies)
+}
+
+/// Reads the ABI of a deployed contract from its Ignition artifact, or from
+/// an `abi` recorded next to its address in `deployed_addresses.json`.
+pub fn load_contract_abi(ctx: &Context, deployment_dir: &Path, contract_key: &str) -> Option<Vec<Value>> {
+    let read_json = |path: PathBuf| -> Option<Value> {
+        serde_json::from_str(strip_bom(&ctx.read_file(&path).ok()??)).ok()
+    };
+    let artifact = read_json(deployment_dir.join("artifacts").join(format!("{}.json", contract_key)));
+    match artifact {
+        Some(artifact) => artifact["abi"].as_array().cloned(),
+        None => read_json(deployment_dir.join("deployed_addresses.json"))
+            .and_then(|addresses| addresses[contract_key]["abi"].as_array().cloned()),
+    }
+}
+
+/// Canonical signature of a function ABI entry, e.g.
+/// `transfer(address,uint256)`, with tuples spelled out as their components.
+/// `None` for other entries (events, errors, constructors, ...).
+pub fn function_signature(entry: &Value) -> Option<String> {
+    if entry["type"].as_str() != Some("function") {
+        return None;
+    }
+    fn canonical_type(param: &Value) -> Option<String> {
+        let ty = param["type"].as_str()?;
+        match ty.strip_prefix("tuple") {
+            Some(array_suffix) => {
+                let components = param["components"].as_array()?.iter()
+                    .map(canonical_type)
+                    .collect::<Option<Vec<_>>>()?;
+                Some(format!("({}){}", components.join(","), array_suffix))
+            }
+            None => Some(ty.to_string()),
+        }
+    }
+    let inputs = match entry["inputs"].as_array() {
+        Some(inputs) => inputs.iter().map(canonical_type).collect::<Option<Vec<_>>>()?,
+        None => Vec::new(),
+    };
+    Some(format!("{}({})", entry["name"].as_str()?, inputs.join(",")))
+}
+
+/// The 4-byte selector of a function signature, as `0x`-prefixed hex.
+pub fn function_selector(signature: &str) -> String {
+    format!("0x{}", rpc::encode_hex(&ens::keccak256(signature.as_bytes())[..4]))
+}
+
+/// A function of a deployed contract sharing its selector with a function of
+/// another contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SelectorFunction {
+    pub contract: String,
+    pub signature: String,
+}
+
+/// A 4-byte selector shared by functions of several contracts deployed on the
+/// same network. A proxy or diamond can only route a selector to one of them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SelectorCollision {
+    pub directory: String,
+    pub chain_id: u64,
+    pub selector: String,
+    pub functions: Vec<SelectorFunction>,
+}
+
+/// Finds function selectors shared across contracts within each deployment,
+/// going by the contracts' ABIs. Contracts without an ABI are skipped, and
+/// unreadable deployments go to [`ErrorLog::network_error`](errors::ErrorLog::network_error).
+pub fn find_selector_collisions(ctx: &Context) -> Result<Vec<SelectorCollision>, String> {
+    let mut collisions = Vec::new();
+
+    for_each_deployment(ctx, |chain_id, path, contracts| {
+        let mut by_selector: BTreeMap<String, Vec<SelectorFunction>> = BTreeMap::new();
+        for (contract, _) in contracts {
+            let Some(abi) = load_contract_abi(ctx, path, &contract) else {
+                continue;
+            };
+            let signatures: HashSet<String> = abi.iter().filter_map(function_signature).collect();
+            for signature in signatures {
+                by_selector.entry(function_selector(&signature))
+                    .or_default()
+                    .push(SelectorFunction { contract: contract.clone(), signature });
+            }
+        }
+
+        for (selector, mut functions) in by_selector {
+            if functions.len() > 1 {
+                functions.sort_by(|a, b| a.contract.cmp(&b.contract));
+                collisions.push(SelectorCollision {
+                    directory: format!("chain-{}", chain_id),
+                    chain_id,
+                    selector,
+                    functions,
+                });
+            }
+        }
+    })?;
+
+    Ok(collisions)
+}
+
+/// A recorded address that isn't a valid address, or whose mixed-case
+/// spelling doesn't match its EIP-55 checksum.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MalformedAddress {
+    pub directory: String,
+    pub chain_id: u64,
+    pub contract: String,
+    pub address: String,
+}
+
+/// Finds every malformed address recorded in the deployments, see
+/// [`is_malformed_address`]. Unreadable deployments go to
+/// [`ErrorLog::network_error`](errors::ErrorLog::network_error).
+pub fn find_malformed_addresses(ctx: &Context) -> Result<Vec<MalformedAddress>, String> {
+    let mut malformed = Vec::new();
+
+    for_each_deployment(ctx, |chain_id, _, contracts| {
+        for (contract, address) in contracts {
+            if is_malformed_address(&address) {
+                malformed.push(MalformedAddress { directory: format!("chain-{}", chain_id), chain_id, contract, address });
+            }
+        }
+    })?;
+
+    Ok(malformed)
+}
+
+/// A recorded address with leading or trailing whitespace, usually left by a
+/// copy-paste. Addresses are trimmed when read, but tools reading the file
+/// directly get the whitespace too.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PaddedAddress {
+    pub directory: String,
+    pub chain_id: u64,
+    pub contract: String,
+    /// The address as written, whitespace included
+    pub address: String,
+}
+
+/// Finds every address recorded with surrounding whitespace, see
+/// [`find_padded_entries`]. Unreadable deployments go to
+/// [`ErrorLog::network_error`](errors::ErrorLog::network_error).
+pub fn find_padded_addresses(ctx: &Context) -> Result<Vec<PaddedAddress>, String> {
+    let mut padded = Vec::new();
+
+    for (chain_id, path) in chain_deployment_dirs(ctx)? {
+        let entries = match ctx.read_file(&path.join("deployed_addresses.json")) {
+            Ok(Some(content)) => find_padded_entries(&content),
+            Ok(None) => continue,
+            Err(e) => Err(e),
+        };
+        let entries = match entries {
+            Ok(entries) => entries,
+            Err(e) => {
+                ctx.errors.network_error(chain_id, format!("Error reading deployment in chain-{}: {}", chain_id, e))?;
+                continue;
+            }
+        };
+        for (contract, address) in entries {
+            padded.push(PaddedAddress { directory: format!("chain-{}", chain_id), chain_id, contract, address });
+        }
+    }
+
+    Ok(padded)
+}
+
+/// Whether `address` is invalid, or written in mixed case that isn't its
+/// EIP-55 checksum form. All-lowercase and all-uppercase addresses carry no
+/// checksum, so they are fine.
+pub fn is_malformed_address(address: &str) -> bool {
+    if !is_valid_address(address) {
+        return true;
+    }
+    let hex = &address[2..];
+    let single_case = hex == hex.to_ascii_lowercase() || hex == hex.to_ascii_uppercase();
+    !single_case && !is_checksummed_address(address)
+}
+
+/// Whether `address` is a 0x-prefixed, 20 byte hex address.
+pub fn is_valid_address(address: &str) -> bool {
+    address.strip_prefix("0x")
+        .is_some_and(|hex| hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Returns the EIP-55 mixed-case checksum form of `address`, or `None` if it
+/// isn't a valid address.
+pub fn checksum_address(address: &str) -> Option<String> {
+    if !is_valid_address(address) {
+        return None;
+    }
+    let hex = address[2..].to_ascii_lowercase();
+    let hash = ens::keccak256(hex.as_bytes());
+    let checksummed: String = hex.chars().enumerate()
+        .map(|(i, c)| {
+            // Each hex character is checked against the matching nibble of the hash
+            let nibble = if i % 2 == 0 { hash[i / 2] >> 4 } else { hash[i / 2] & 0x0f };
+            if nibble >= 8 { c.to_ascii_uppercase() } else { c }
+        })
+        .collect();
+    Some(format!("0x{}", checksummed))
+}
+
+/// Whether `address` is written in its EIP-55 checksum form.
+pub fn is_checksummed_address(address: &str) -> bool {
+    checksum_address(address).is_some_and(|checksummed| checksummed == address)
+}
+
+/// Outcome of comparing a contract's on-chain code hash with the expected one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodeHashStatus {
+    Match,
+    Mismatch,
+    /// Nothing is deployed at the address
+    NoCode,
+    /// No expected hash was given for the contract
+    Unchecked,
+    /// The node couldn't be asked for the code, so nothing is known about it
+    RpcError,
+}
+
+impl CodeHashStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CodeHashStatus::Match => "match",
+            CodeHashStatus::Mismatch => "mismatch",
+            CodeHashStatus::NoCode => "no code",
+            CodeHashStatus::Unchecked => "unchecked",
+            CodeHashStatus::RpcError => "RPC error",
+        }
+    }
+
+    /// Whether the contract failed verification.
+    pub fn failed(self) -> bool {
+        matches!(self, CodeHashStatus::Mismatch | CodeHashStatus::NoCode | CodeHashStatus::RpcError)
+    }
+}
+
+/// Probable reason why there is no code at a recorded address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoCodeCause {
+    /// The address has sent transactions or holds a balance, so it's most
+    /// likely an externally owned account and the wrong address was recorded
+    LikelyEoa,
+    /// The address has no activity at all: the contract self-destructed, or
+    /// was never deployed to this network
+    SelfDestructedOrNeverDeployed,
+}
+
+impl NoCodeCause {
+    pub fn description(self) -> &'static str {
+        match self {
+            NoCodeCause::LikelyEoa => "the address has a nonce or balance, so it's likely an EOA and the wrong address was recorded",
+            NoCodeCause::SelfDestructedOrNeverDeployed => "the address has no nonce or balance, so the contract self-destructed or was never deployed to this network",
+        }
+    }
+}
+
+/// Looks at the nonce and balance of an address without code to guess why
+/// the code is missing.
+pub fn explain_no_code(rpc: &rpc::RpcClient, address: &str) -> Result<NoCodeCause, String> {
+    let nonce = rpc.eth_get_transaction_count(address)?;
+    let balance = rpc.eth_get_balance(address)?;
+    Ok(if nonce > 0 || balance > 0 { NoCodeCause::LikelyEoa } else { NoCodeCause::SelfDestructedOrNeverDeployed })
+}
+
+/// The code hash verification of one deployed contract.
+#[derive(Debug, Clone, Serialize)]
+pub struct CodeHashCheck {
+    pub contract: String,
+    pub address: String,
+    pub expected: Option<String>,
+    /// keccak256 of the runtime bytecode, if any code is deployed
+    pub actual: Option<String>,
+    pub status: CodeHashStatus,
+    /// Probable cause of a `no_code` status, when asked for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_code_cause: Option<NoCodeCause>,
+    /// Why the node couldn't be queried, for an `rpc_error` status or when
+    /// the cause of a `no_code` status or the on-chain time couldn't be
+    /// looked up
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// When `deployed_addresses.json` was last modified, in seconds since the
+    /// Unix epoch, when on-chain times are asked for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_modified: Option<u64>,
+    /// When the deployment transaction was mined, in seconds since the Unix
+    /// epoch, when on-chain times are asked for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mined_at: Option<u64>,
+}
+
+/// Reads a JSON file mapping contract names (or full `Module#Contract` keys)
+/// to the expected keccak256 hash of their runtime bytecode.
+pub fn load_expected_code_hashes(path: &Path) -> Result<BTreeMap<String, String>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read expected hashes file {}: {}", path.display(), e))?;
+    serde_json::from_str(strip_bom(&content))
+        .map_err(|e| format!("Failed to parse expected hashes file {}: {}", path.display(), e))
+}
+
+/// Returns the 0x-prefixed keccak256 hash of hex encoded bytecode, or `None`
+/// if it isn't valid hex.
+pub fn code_hash(code: &str) -> Option<String> {
+    let bytes = rpc::decode_hex(code)?;
+    Some(format!("0x{}", rpc::encode_hex(&ens::keccak256(&bytes))))
+}
+
+/// Compares the runtime code hash (`keccak256(eth_getCode(address))`) of each
+/// contract deployed to `network` against `expected`. Contracts without an
+/// expected hash are reported as unchecked without querying the node. With
+/// `explain_missing_code`, addresses without code are looked at further with
+/// [`explain_no_code`]. A contract whose code can't be fetched (after the
+/// client's retries) gets an `rpc_error` status instead of failing the run.
+/// With `onchain_time`, each contract also gets the time its deployment
+/// transaction was mined, see [`deployment_block_time`], next to the
+/// modification time of `deployed_addresses.json`.
+pub fn verify_code_hashes(ctx: &Context, network: &str, rpc: &rpc::RpcClient, expected: &BTreeMap<String, String>, explain_missing_code: bool, onchain_time: bool) -> Result<Vec<CodeHashCheck>, String> {
+    let networks = parse_hardhat_config(ctx)?;
+    let chain_id = *networks.get(network)
+        .ok_or_else(|| format!("Network {} not found in {}", network, ctx.config_name()))?;
+    let deployment_dir = deployments_dir(ctx)?.join(format!("chain-{}", chain_id));
+    let contracts = get_deployment_contracts(ctx, &deployment_dir)?
+        .ok_or_else(|| format!("No deployment found for network {}", network))?;
+    let file_modified = ctx.modified(&deployment_dir.join("deployed_addresses.json"))
+        .and_then(|modified| modified.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|since_epoch| since_epoch.as_secs());
+
+    let mut checks = Vec::new();
+    for (contract, address) in contracts {
+        let expected_hash = expected.iter()
+            .find(|(name, _)| contract_matches(&contract, name))
+            .map(|(_, hash)| hash.clone());
+        let mut check = check_code_hash(rpc, contract, address, expected_hash, explain_missing_code);
+        if onchain_time {
+            check.file_modified = file_modified;
+            match deployment_block_time(ctx, &deployment_dir, rpc, &check.contract) {
+                Ok(mined_at) => check.mined_at = mined_at,
+                Err(e) => check.error = Some(match check.error.take() {
+                    Some(error) => format!("{}; {}", error, e),
+                    None => e,
+                }),
+            }
+        }
+        checks.push(check);
+    }
+
+    Ok(checks)
+}
+
+fn check_code_hash(rpc: &rpc::RpcClient, contract: String, address: String, expected: Option<String>, explain_missing_code: bool) -> CodeHashCheck {
+    let mut check = CodeHashCheck {
+        contract,
+        address,
+        expected,
+        actual: None,
+        status: CodeHashStatus::Unchecked,
+        no_code_cause: None,
+        error: None,
+        file_modified: None,
+        mined_at: None,
+    };
+    let Some(expected) = &check.expected else {
+        return check;
+    };
+
+    check.actual = match fetch_code_hash(rpc, &check.address) {
+        Ok(actual) => actual,
+        Err(e) => {
+            check.status = CodeHashStatus::RpcError;
+            check.error = Some(e);
+            return check;
+        }
+    };
+    check.status = match &check.actual {
+        None => CodeHashStatus::NoCode,
+        Some(actual) if actual.eq_ignore_ascii_case(expected) => CodeHashStatus::Match,
+        Some(_) => CodeHashStatus::Mismatch,
+    };
+    if explain_missing_code && check.status == CodeHashStatus::NoCode {
+        match explain_no_code(rpc, &check.address) {
+            Ok(cause) => check.no_code_cause = Some(cause),
+            Err(e) => check.error = Some(e),
+        }
+    }
+    check
+}
+
+/// The hash of the transaction that deployed `contract_key`, and the block it
+/// was mined in if the journal recorded its receipt, going by the Ignition
+/// `journal.jsonl` of a deployment. The last transaction sent for the
+/// contract wins, since earlier ones may have been replaced.
+pub fn deployment_transaction(ctx: &Context, deployment_dir: &Path, contract_key: &str) -> Option<(String, Option<u64>)> {
+    let journal = ctx.read_file(&deployment_dir.join("journal.jsonl")).ok()??;
+    let mut found = None;
+    for line in journal.lines() {
+        let Ok(entry) = serde_json::from_str::<Value>(strip_bom(line)) else {
+            continue;
+        };
+        if entry["futureId"].as_str() != Some(contract_key) {
+            continue;
+        }
+        match entry["type"].as_str() {
+            Some("TRANSACTION_SEND") => {
+                if let Some(hash) = entry["transaction"]["hash"].as_str().or_else(|| entry["hash"].as_str()) {
+                    found = Some((hash.to_string(), None));
+                }
+            }
+            Some("TRANSACTION_CONFIRM") => {
+                if let Some(hash) = entry["hash"].as_str() {
+                    found = Some((hash.to_string(), entry["receipt"]["blockNumber"].as_u64()));
+                }
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+/// When the transaction that deployed `contract_key` was mined, in seconds
+/// since the Unix epoch, read from the timestamp of its block. `None` if the
+/// journal doesn't record the transaction, or the node doesn't know it.
+pub fn deployment_block_time(ctx: &Context, deployment_dir: &Path, rpc: &rpc::RpcClient, contract_key: &str) -> Result<Option<u64>, String> {
+    let Some((hash, block_number)) = deployment_transaction(ctx, deployment_dir, contract_key) else {
+        return Ok(None);
+    };
+    let block_number = match block_number {
+        Some(block_number) => block_number,
+        None => match rpc.eth_get_transaction_block(&hash)? {
+            Some(block_number) => block_number,
+            None => return Ok(None),
+        },
+    };
+    rpc.eth_get_block_timestamp(block_number)
+}
+
+/// The hash of the code deployed at `address`, or `None` if there is none.
+fn fetch_code_hash(rpc: &rpc::RpcClient, address: &str) -> Result<Option<String>, String> {
+    let code = rpc.request("eth_getCode", serde_json::json!([address, "latest"]))?;
+    let code = code.as_str().ok_or_else(|| format!("Invalid eth_getCode result for {}", address))?;
+    if code.trim_start_matches("0x").is_empty() {
+        return Ok(None);
+    }
+    code_hash(code).map(Some).ok_or_else(|| format!("Invalid bytecode returned for {}", address))
+}
+
+/// One check run by [`build_health_report`].
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthCheck {
+    pub name: String,
+    pub passed: bool,
+    pub details: String,
+    /// Bit set in the exit code when the check fails
+    pub bit: i32,
+}
+
+/// Combined result of the count, audit and address checks.
+#[derive(Debug, Serialize)]
+pub struct HealthReport {
+    pub checks: Vec<HealthCheck>,
+    /// Bitmask of the failed checks' bits, 0 when everything passed. Bit 1 is
+    /// never set so it stays free for errors running the checks.
+    pub exit_code: i32,
+}
+
+impl HealthReport {
+    /// How many checks failed.
+    pub fn failed(&self) -> usize {
+        self.checks.iter().filter(|check| !check.passed).count()
+    }
+}
+
+/// Runs every deployment check a CI pipeline cares about in one go.
+pub fn build_health_report(ctx: &Context) -> Result<HealthReport, String> {
+    let deployment_count = count_deployments(ctx)?;
+    let audit = build_audit_report(ctx, None, None, None, false)?;
+    let list = build_list_report(ctx, ContractSelection::All)?;
+
+    let invalid_addresses: Vec<String> = list.deployments.iter()
+        .flat_map(|deployment| deployment.contracts.iter().map(move |contract| (deployment, contract)))
+        .filter(|(_, contract)| !is_valid_address(&contract.address))
+        .map(|(deployment, contract)| format!("{} {}: {}", deployment.network, contract.name, contract.address))
+        .collect();
+
+    let mut config_mismatches = Vec::new();
+    if !audit.config_without_deployment.is_empty() {
+        config_mismatches.push(format!("{} network(s) in config without deployments", audit.config_without_deployment.len()));
+    }
+    if !audit.deployment_without_config.is_empty() {
+        config_mismatches.push(format!("{} deployment(s) without config entries", audit.deployment_without_config.len()));
+    }
+
+    let check = |name: &str, bit: i32, failures: Vec<String>| HealthCheck {
+        name: name.to_string(),
+        passed: failures.is_empty(),
+        details: failures.join(", "),
+        bit,
+    };
+    let mut checks = vec![
+        HealthCheck {
+            name: "Deployments".to_string(),
+            passed: deployment_count > 0,
+            details: format!("{} deployment(s)", deployment_count),
+            bit: 2,
+        },
+        check("Config", 4, config_mismatches),
+        check("Network names", 8, audit.similar_network_names.iter()
+            .map(|group| group.networks.join("/"))
+            .collect()),
+        check("Chain IDs", 16, audit.chain_id_mismatches.iter()
+            .map(|mismatch| format!("{} records chain ID {}", mismatch.directory, mismatch.recorded_chain_id))
+            .collect()),
+        check("Addresses", 32, invalid_addresses),
+        check("Deployment files", 64, list.errors),
+    ];
+    for check in &mut checks {
+        if check.passed && check.details.is_empty() {
+            check.details = "OK".to_string();
+        }
+    }
+
+    let exit_code = checks.iter()
+        .filter(|check| !check.passed)
+        .fold(0, |code, check| code | check.bit);
+    Ok(HealthReport { checks, exit_code })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// A throwaway hardhat project on disk.
+    struct Project {
+        dir: TempDir,
+    }
+
+    impl Project {
+        fn new(config: &str) -> Self {
+            let dir = tempfile::tempdir().unwrap();
+            fs::write(dir.path().join("hardhat.config.ts"), config).unwrap();
+            Project { dir }
+        }
+
+        fn root(&self) -> &Path {
+            self.dir.path()
+        }
+
+        fn ctx(&self) -> Context {
+            Context::new(self.root())
+        }
+
+        /// Creates `deployments/<name>`, with `deployed_addresses.json` when
+        /// `addresses` is given.
+        fn deployment(&self, name: &str, addresses: Option<&str>) -> PathBuf {
+            let path = self.root().join("deployments").join(name);
+            fs::create_dir_all(&path).unwrap();
+            if let Some(addresses) = addresses {
+                fs::write(path.join("deployed_addresses.json"), addresses).unwrap();
+            }
+            path
+        }
+
+        /// Runs git in the project directory, asserting that it succeeds.
+        fn git(&self, args: &[&str]) {
+            let status = std::process::Command::new("git")
+                .arg("-C").arg(self.root())
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success());
+        }
+
+        /// Commits every change in the project directory.
+        fn commit(&self, message: &str) {
+            self.git(&["add", "-A"]);
+            self.git(&["commit", "-q", "-m", message]);
+        }
+    }
+
+    const CONFIG: &str = r#"
+const config: HardhatUserConfig = {
+  networks: {
+    hardhat: {},
+    base: {
+      url: "https://mainnet.base.org",
+      chainId: 8453,
+    },
+    baseSepolia: { url: "https://sepolia.base.org", chainId: 84532 },
+  },
+};
+"#;
+
+    #[test]
+    fn validate_hardhat_project_requires_config() {
+        let project = Project::new(CONFIG);
+        assert!(validate_hardhat_project(&project.ctx()).is_ok());
+        let empty = tempfile::tempdir().unwrap();
+        let err = validate_hardhat_project(&Context::new(empty.path())).unwrap_err();
+        assert!(err.contains("No hardhat.config.ts found"));
+    }
+
+    #[test]
+    fn require_config_networks_rejects_configs_without_networks() {
+        assert!(require_config_networks(&Project::new(CONFIG).ctx()).is_ok());
+
+        let project = Project::new("export default { networks: { hardhat: {} } };");
+        let err = require_config_networks(&project.ctx()).unwrap_err();
+        assert!(err.contains("No networks"), "{}", err);
+
+        fs::remove_file(project.root().join("hardhat.config.ts")).unwrap();
+        assert!(require_config_networks(&project.ctx()).is_err());
+    }
+
+    #[test]
+    fn parse_hardhat_config_reads_networks() {
+        let project = Project::new(CONFIG);
+        let networks = parse_hardhat_config(&project.ctx()).unwrap();
+
+        assert_eq!(networks.len(), 2);
+        assert_eq!(networks["base"], 8453);
+        assert_eq!(networks["baseSepolia"], 84532);
+    }
+
+    #[test]
+    fn parse_hardhat_config_ignores_commented_chain_ids() {
+        let project = Project::new(r#"
+const config = {
+  networks: {
+    hardhat: {},
+    base: {
+      // chainId: 1 (old)
+      url: "https://mainnet.base.org",
+      chainId: 8453,
+    },
+    baseSepolia: { url: 'https://sepolia.base.org', chainId: 84532, /* chainId: 5, */ },
+    // optimism: { chainId: 10 },
+    /*
+    arbitrum: { chainId: 42161 },
+    */
+  },
+};
+"#);
+        let networks = parse_hardhat_config(&project.ctx()).unwrap();
+
+        assert_eq!(networks.len(), 2);
+        assert_eq!(networks["base"], 8453);
+        assert_eq!(networks["baseSepolia"], 84532);
+    }
+
+    #[test]
+    fn parse_hardhat_config_reads_bigint_chain_ids() {
+        let project = Project::new(r#"
+const config = {
+  networks: {
+    hardhat: {},
+    sepolia: { chainId: 11155111n },
+    l3: { chainId: 123456789012345678n },
+    l3Legacy: { chainId: 123456789012345678 },
+  },
+};
+"#);
+        let networks = parse_hardhat_config(&project.ctx()).unwrap();
+
+        assert_eq!(networks["sepolia"], 11155111);
+        assert_eq!(networks["l3"], 123456789012345678);
+        assert_eq!(networks["l3Legacy"], 123456789012345678);
+    }
+
+    #[test]
+    fn parse_hardhat_config_reads_hex_chain_ids() {
+        let project = Project::new(r#"
+const config = {
+  networks: {
+    hardhat: {},
+    base: { chainId: 0x2105 },
+    baseSepolia: { chainId: 84532 },
+    sepolia: { chainId: 0xAA36A7n },
+  },
+};
+"#);
+        let networks = parse_hardhat_config(&project.ctx()).unwrap();
+
+        assert_eq!(networks.len(), 3);
+        assert_eq!(networks["base"], 8453);
+        assert_eq!(networks["baseSepolia"], 84532);
+        assert_eq!(networks["sepolia"], 11155111);
+    }
+
+    #[test]
+    fn parse_hardhat_config_resolves_constant_chain_ids() {
+        let project = Project::new(r#"
+const BASE_ID = 8453;
+const SEPOLIA_ID: number = 0xaa36a7;
+const RPC_URL = "https://mainnet.base.org";
+
+const config = {
+  networks: {
+    hardhat: {},
+    base: { url: RPC_URL, chainId: BASE_ID },
+    sepolia: { chainId: SEPOLIA_ID },
+    baseSepolia: { chainId: 84532 },
+    fromEnv: { chainId: Number(process.env.CHAIN_ID) },
+  },
+};
+"#);
+        let networks = parse_hardhat_config(&project.ctx()).unwrap();
+
+        assert_eq!(networks.len(), 3);
+        assert_eq!(networks["base"], 8453);
+        assert_eq!(networks["sepolia"], 11155111);
+        assert_eq!(networks["baseSepolia"], 84532);
+    }
+
+    #[test]
+    fn parse_hardhat_config_resolves_constant_chain_ids_in_arrays() {
+        let project = Project::new(r#"
+const BASE_ID = 8453n;
+export const networks = [
+  { name: "base", chainId: BASE_ID },
+  { name: "baseSepolia", chainId: 84532 },
+];
+"#);
+        let networks = parse_hardhat_config(&project.ctx()).unwrap();
+
+        assert_eq!(networks["base"], 8453);
+        assert_eq!(networks["baseSepolia"], 84532);
+    }
+
+    #[test]
+    fn parse_hardhat_config_chain_id_overflow() {
+        let project = Project::new("const config = { networks: { hardhat: {}, huge: { chainId: 123456789012345678901n } } };");
+        let err = parse_hardhat_config(&project.ctx()).unwrap_err();
+        assert_eq!(err, format!("Chain ID 123456789012345678901 for network huge is too large (the maximum is {})", u64::MAX));
+    }
+
+    #[test]
+    fn parse_hardhat_config_reads_networks_array() {
+        let project = Project::new(r#"
+const config = {
+  networks: [
+    { name: "base", url: "https://mainnet.base.org", chainId: 8453 },
+    {
+      chainId: 84532n,
+      name: 'baseSepolia',
+    },
+    { name: "local" },
+  ],
+};
+"#);
+        let networks = parse_hardhat_config(&project.ctx()).unwrap();
+
+        assert_eq!(networks.len(), 2);
+        assert_eq!(networks["base"], 8453);
+        assert_eq!(networks["baseSepolia"], 84532);
+    }
+
+    #[test]
+    fn parse_default_network_reads_quoted_name() {
+        let project = Project::new("const config = { defaultNetwork: 'baseSepolia', networks: {} };");
+        assert_eq!(parse_default_network(&project.ctx()).unwrap().as_deref(), Some("baseSepolia"));
+
+        let project = Project::new(CONFIG);
+        assert_eq!(parse_default_network(&project.ctx()).unwrap(), None);
+    }
+
+    #[test]
+    fn build_list_report_ignores_hardhat_default_network() {
+        let project = Project::new("const config = { defaultNetwork: \"hardhat\", networks: {} };");
+        assert_eq!(build_list_report(&project.ctx(), ContractSelection::First).unwrap().default_network, None);
+    }
+
+    #[test]
+    fn parse_hardhat_config_without_networks() {
+        let project = Project::new("export default {};");
+        assert!(parse_hardhat_config(&project.ctx()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_hardhat_config_merges_network_config_json() {
+        let project = Project::new(CONFIG);
+        fs::write(project.root().join("network-config.json"), r#"{
+            "base": { "chainId": 1 },
+            "optimism": { "url": "https://mainnet.optimism.io", "chainId": 10 },
+            "sepolia": 11155111,
+            "l3": "123456789012345678n",
+            "localhost": { "url": "http://127.0.0.1:8545" }
+        }"#).unwrap();
+
+        let networks = parse_hardhat_config(&project.ctx()).unwrap();
+        assert_eq!(networks.len(), 5);
+        assert_eq!(networks["base"], 8453);
+        assert_eq!(networks["optimism"], 10);
+        assert_eq!(networks["sepolia"], 11155111);
+        assert_eq!(networks["l3"], 123456789012345678);
+    }
+
+    #[test]
+    fn parse_network_urls_reads_literals() {
+        let project = Project::new(r#"
+const config = {
+  networks: {
+    hardhat: {},
+    base: { url: "https://mainnet.base.org", chainId: 8453 },
+    baseSepolia: { url: process.env.BASE_SEPOLIA_RPC, chainId: 84532 },
+    ethereum: { url: `https://eth-mainnet.g.alchemy.com/v2/${ALCHEMY_KEY}`, chainId: 1 },
+    optimism: { url: `https://mainnet.optimism.io`, chainId: 10 },
+  },
+};
+"#);
+        let urls = parse_network_urls(&project.ctx()).unwrap();
+        assert_eq!(urls.len(), 3);
+        assert_eq!(urls["base"], ConfigUrl { url: "https://mainnet.base.org".to_string(), partial: false });
+        assert_eq!(urls["ethereum"], ConfigUrl { url: "https://eth-mainnet.g.alchemy.com/v2/${ALCHEMY_KEY}".to_string(), partial: true });
+        assert_eq!(urls["optimism"], ConfigUrl { url: "https://mainnet.optimism.io".to_string(), partial: false });
+        assert_eq!(parse_hardhat_config(&project.ctx()).unwrap()["ethereum"], 1);
+    }
+
+    #[test]
+    fn config_parsers_ignore_comments() {
+        let project = Project::new(r#"
+const config = {
+  // defaultNetwork: "base",
+  networks: {
+    base: {
+      // url: "https://old.base.org",
+      url: process.env.BASE_RPC,
+      chainId: 8453,
+    },
+    /* optimism: { url: "https://mainnet.optimism.io", chainId: 10 }, */
+    ethereum: { url: "https://eth.llamarpc.com", chainId: 1 },
+  },
+};
+"#);
+        assert_eq!(parse_default_network(&project.ctx()).unwrap(), None);
+
+        let urls = parse_network_urls(&project.ctx()).unwrap();
+        assert_eq!(urls.len(), 1);
+        assert_eq!(urls["ethereum"], ConfigUrl { url: "https://eth.llamarpc.com".to_string(), partial: false });
+    }
+
+    #[test]
+    fn parse_hardhat_config_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = parse_hardhat_config(&Context::new(dir.path())).unwrap_err();
+        assert!(err.starts_with("Failed to read hardhat.config.ts"));
+    }
+
+    #[test]
+    fn count_deployments_without_deployments_dir() {
+        let project = Project::new(CONFIG);
+        assert_eq!(count_deployments(&project.ctx()).unwrap(), 0);
+    }
+
+    #[test]
+    fn count_deployments_counts_directories_only() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", None);
+        project.deployment("chain-84532", None);
+        fs::write(project.root().join("deployments").join("notes.txt"), "").unwrap();
+
+        assert_eq!(count_deployments(&project.ctx()).unwrap(), 2);
+    }
+
+    #[test]
+    fn count_deployments_skips_ignored_and_hidden_dirs() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", None);
+        project.deployment("chain-31337", None);
+        project.deployment(".cache", None);
+
+        let mut ctx = project.ctx();
+        ctx.ignore_dirs = vec!["chain-31337".to_string()];
+        assert_eq!(count_deployments(&ctx).unwrap(), 1);
+    }
+
+    #[test]
+    fn find_chain_id_mismatches_checks_addresses_and_journal() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", Some(r#"{"chainId": 8453, "TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"}"#));
+        project.deployment("chain-84532", Some(r#"{"chainId": 8453, "TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"}"#));
+        let copied = project.deployment("chain-1", Some("{}"));
+        fs::write(copied.join("journal.jsonl"), "{\"chainId\":10,\"type\":\"DEPLOYMENT_INITIALIZE\"}\n{\"type\":\"RUN_START\"}\n").unwrap();
+        project.deployment("chain-10", Some("{}"));
+
+        assert_eq!(find_chain_id_mismatches(&project.ctx()).unwrap(), vec![
+            ChainIdMismatch { directory: "chain-1".to_string(), directory_chain_id: 1, recorded_chain_id: 10 },
+            ChainIdMismatch { directory: "chain-84532".to_string(), directory_chain_id: 84532, recorded_chain_id: 8453 },
+        ]);
+    }
+
+    #[test]
+    fn build_list_report_primary_contract() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", Some(r#"{
+            "TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+            "VaultModule#Vault": "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512"
+        }"#));
+        project.deployment("chain-84532", Some(r#"{"TokenModule#Token": "0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0"}"#));
+
+        let pattern = Regex::new("^Vault$").unwrap();
+        let report = build_list_report(&project.ctx(), ContractSelection::Primary(&pattern)).unwrap();
+        let selected: Vec<(&str, &str)> = report.deployments.iter()
+            .map(|deployment| (deployment.network.as_str(), deployment.contracts[0].name.as_str()))
+            .collect();
+
+        assert!(report.deployments.iter().all(|deployment| deployment.contracts.len() == 1));
+        assert_eq!(selected, vec![("base", "VaultModule#Vault"), ("baseSepolia", "TokenModule#Token")]);
+    }
+
+    #[test]
+    fn contract_names_shared_by_modules_are_ambiguous() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", Some(r#"{
+            "ModA#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+            "ModB#Token": "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512"
+        }"#));
+        let ctx = project.ctx();
+
+        assert_eq!(resolve_deployment(&ctx, "base", Some("Token")).unwrap_err(), "Contract Token is ambiguous, it matches ModA#Token, ModB#Token on network base");
+        assert_eq!(resolve_deployment(&ctx, "base", Some("modb#token")).unwrap().1, "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512");
+
+        let pattern = Regex::new("^Token$").unwrap();
+        let report = build_list_report(&ctx, ContractSelection::Primary(&pattern)).unwrap();
+        assert!(report.deployments.is_empty());
+        assert_eq!(report.errors, ["Error reading deployment for base: Primary contract pattern ^Token$ matches ModA#Token, ModB#Token"]);
+
+        let report = build_list_report(&ctx, ContractSelection::All).unwrap();
+        let contracts = &report.deployments[0].contracts;
+        let names: Vec<String> = contracts.iter().map(|contract| contract.display_name_without_module(contracts)).collect();
+        assert_eq!(names, ["ModA#Token", "ModB#Token"]);
+        assert_eq!(contracts[0].display_name_without_module(&contracts[..1]), "Token");
+    }
+
+    #[test]
+    fn list_report_retains_networks() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", Some(r#"{"VaultModule#Vault": "0x5FbDB2315678afecb367f032d93F642f64180aa3", "TokenModule#Token": "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512"}"#));
+
+        let pattern = Regex::new("^Vault").unwrap();
+        let mut report = build_list_report(&project.ctx(), ContractSelection::Pattern(&pattern)).unwrap();
+        report.retain_networks(&["base".to_string(), "optimism".to_string()]);
+        assert_eq!(report.deployments.len(), 1);
+        assert_eq!(report.deployments[0].contracts.len(), 1);
+        assert_eq!(report.deployments[0].contracts[0].name, "VaultModule#Vault");
+        assert!(report.missing.is_empty());
+        assert_eq!(report.warnings, ["Network optimism not found"]);
+    }
+
+    #[test]
+    fn split_contract_key_separates_module() {
+        assert_eq!(split_contract_key("TokenModule#Token"), (Some("TokenModule"), "Token"));
+        assert_eq!(split_contract_key("Token"), (None, "Token"));
+    }
+
+    #[test]
+    fn proxy_links_follow_modules() {
+        let contract = |name: &str, address: &str| DeployedContract { name: name.to_string(), address: address.to_string(), external: false, tags: Vec::new(), alias: None };
+        let deployment = NetworkDeployment {
+            network: "base".to_string(),
+            chain_id: 8453,
+            contracts: vec![
+                contract("ProxyModule#Box", "0x0000000000000000000000000000000000000001"),
+                contract("ProxyModule#TransparentUpgradeableProxy", "0x0000000000000000000000000000000000000002"),
+                contract("ProxyModule#ProxyAdmin", "0x0000000000000000000000000000000000000003"),
+                contract("ProxyModule#UpgradeableBox", "0x0000000000000000000000000000000000000002"),
+                contract("TokenModule#Token", "0x0000000000000000000000000000000000000004"),
+            ],
+            commit: None,
+        };
+
+        assert_eq!(proxy_links(&deployment), vec![
+            ProxyLink { from: "ProxyModule#TransparentUpgradeableProxy".to_string(), to: "ProxyModule#Box".to_string(), role: ProxyRole::Implementation },
+            ProxyLink { from: "ProxyModule#ProxyAdmin".to_string(), to: "ProxyModule#TransparentUpgradeableProxy".to_string(), role: ProxyRole::Admin },
+        ]);
+    }
+
+    #[test]
+    fn network_slug_normalizes_names() {
+        assert_eq!(network_slug("baseSepolia"), "base-sepolia");
+        assert_eq!(network_slug("base_sepolia"), "base-sepolia");
+        assert_eq!(network_slug("Base Sepolia"), "base-sepolia");
+        assert_eq!(network_slug("arbitrumOne2"), "arbitrum-one2");
+        assert_eq!(network_slug("base"), "base");
+    }
+
+    #[test]
+    fn is_malformed_address_checks_format_and_checksum() {
+        assert!(!is_malformed_address("0x5FbDB2315678afecb367f032d93F642f64180aa3"));
+        assert!(!is_malformed_address("0x5fbdb2315678afecb367f032d93f642f64180aa3"));
+        assert!(!is_malformed_address("0x5FBDB2315678AFECB367F032D93F642F64180AA3"));
+        assert!(is_malformed_address("0x5FbDB2315678afecb367f032d93F642f64180aA3"));
+        assert!(is_malformed_address("0x5FbDB2315678afecb367f032d93F642f64180a"));
+    }
+
+    #[test]
+    fn find_malformed_addresses_reports_entries() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", Some(r#"{"TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3", "TokenModule#Vault": "0xnot-an-address"}"#));
+        project.deployment("chain-84532", Some(r#"{"TokenModule#Token": "0x5fbdb2315678afecb367f032d93f642f64180aa3"}"#));
+
+        let malformed = find_malformed_addresses(&project.ctx()).unwrap();
+        assert_eq!(malformed, vec![MalformedAddress {
+            directory: "chain-8453".to_string(),
+            chain_id: 8453,
+            contract: "TokenModule#Vault".to_string(),
+            address: "0xnot-an-address".to_string(),
+        }]);
+
+        let report = build_audit_report(&project.ctx(), None, None, None, false).unwrap();
+        assert_eq!(report.malformed_addresses, malformed);
+        assert_eq!(report.summary().malformed_addresses, 1);
+    }
+
+    #[test]
+    fn list_report_redacts_addresses() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", Some(r#"{"TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3", "VaultModule#Vault": "0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0"}"#));
+
+        let mut report = build_list_report(&project.ctx(), ContractSelection::All).unwrap();
+        report.redact_addresses();
+        let contracts: Vec<(&str, &str)> = report.deployments[0].contracts.iter()
+            .map(|contract| (contract.name.as_str(), contract.address.as_str()))
+            .collect();
+        assert_eq!(contracts, vec![("TokenModule#Token", REDACTED_ADDRESS), ("VaultModule#Vault", REDACTED_ADDRESS)]);
+        assert_eq!(report.counts().total_contracts, 2);
+    }
+
+    #[test]
+    fn padded_addresses_are_trimmed_and_reported() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", Some(r#"{"TokenModule#Token": " 0x5FbDB2315678afecb367f032d93F642f64180aa3\n", "TokenModule#Vault": "0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0"}"#));
+
+        let report = build_list_report(&project.ctx(), ContractSelection::First).unwrap();
+        assert_eq!(report.deployments[0].contracts[0].address, "0x5FbDB2315678afecb367f032d93F642f64180aa3");
+        assert_eq!(report.warnings, vec!["Address of TokenModule#Token on base has surrounding whitespace"]);
+
+        let padded = build_audit_report(&project.ctx(), None, None, None, false).unwrap().padded_addresses;
+        assert_eq!(padded, vec![PaddedAddress {
+            directory: "chain-8453".to_string(),
+            chain_id: 8453,
+            contract: "TokenModule#Token".to_string(),
+            address: " 0x5FbDB2315678afecb367f032d93F642f64180aa3\n".to_string(),
+        }]);
+    }
+
+    #[test]
+    fn is_valid_address_checks_length_and_hex() {
+        assert!(is_valid_address("0x5FbDB2315678afecb367f032d93F642f64180aa3"));
+        assert!(!is_valid_address("5FbDB2315678afecb367f032d93F642f64180aa3"));
+        assert!(!is_valid_address("0x5FbDB2315678afecb367f032d93F642f64180aa"));
+        assert!(!is_valid_address("0x5FbDB2315678afecb367f032d93F642f64180aaZ"));
+    }
+
+    #[test]
+    fn build_health_report_sets_failed_bits() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", Some(r#"{"TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"}"#));
+        assert_eq!(build_health_report(&project.ctx()).unwrap().exit_code, 4);
+
+        project.deployment("chain-84532", Some(r#"{"TokenModule#Token": "0x1234"}"#));
+        let report = build_health_report(&project.ctx()).unwrap();
+        let failed: Vec<&str> = report.checks.iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.name.as_str())
+            .collect();
+
+        assert_eq!(failed, vec!["Addresses"]);
+        assert_eq!(report.exit_code, 32);
+
+        let mut ctx = project.ctx();
+        ctx.ignore_dirs = vec!["chain-84532".to_string()];
+        assert_eq!(build_health_report(&ctx).unwrap().exit_code, 0);
+    }
+
+    #[test]
+    fn deployments_file_is_an_error() {
+        let project = Project::new(CONFIG);
+        fs::write(project.root().join("deployments"), "").unwrap();
+
+        let err = count_deployments(&project.ctx()).unwrap_err();
+        assert!(err.ends_with("deployments exists but is not a directory"));
+        assert!(build_list_report(&project.ctx(), ContractSelection::First).is_err());
+        assert!(build_audit_report(&project.ctx(), None, None, None, false).is_err());
+    }
+
+    #[test]
+    fn build_list_report_contract_pattern() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", Some(r#"{
+            "TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+            "TokenModule#VaultV2": "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512",
+            "VaultModule#Vault": "0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0"
+        }"#));
+        project.deployment("chain-84532", Some(r#"{"TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"}"#));
+
+        let pattern = Regex::new("^Vault").unwrap();
+        let report = build_list_report(&project.ctx(), ContractSelection::Pattern(&pattern)).unwrap();
+        let names: Vec<&str> = report.deployments[0].contracts.iter()
+            .map(|contract| contract.name.as_str())
+            .collect();
+
+        assert_eq!(report.deployments.len(), 1);
+        assert_eq!(names, vec!["TokenModule#VaultV2", "VaultModule#Vault"]);
+        assert_eq!(report.missing.len(), 1);
+    }
+
+    #[test]
+    fn build_audit_report_names_unconfigured_deployments() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-42161", None);
+        project.deployment("chain-999111", None);
+
+        let report = build_audit_report(&project.ctx(), None, None, None, false).unwrap();
+        let unconfigured: Vec<(u64, Option<&str>)> = report.deployment_without_config.iter()
+            .map(|deployment| (deployment.chain_id, deployment.probable_network.as_deref()))
+            .collect();
+
+        assert_eq!(unconfigured, vec![(42161, Some("Arbitrum One")), (999111, None)]);
+    }
+
+    #[test]
+    fn build_audit_report_chains_below_floor() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", None);
+        project.deployment("chain-31337", None);
+        project.deployment("chain-1337", None);
+
+        let report = build_audit_report(&project.ctx(), Some(84532), None, None, false).unwrap();
+        assert_eq!(report.chains_below_floor, vec![1337, 8453, 31337]);
+        assert_eq!(report.summary(), AuditSummary {
+            config_without_deployment: 2,
+            deployment_without_config: 2,
+            similar_network_names: 0,
+            chain_id_mismatches: 0,
+            chains_below_floor: 3,
+            checksum_inconsistencies: 0,
+            old_deployments: 0,
+            expected_without_deployment: 0,
+            deployment_not_expected: 0,
+            selector_collisions: 0,
+            padded_addresses: 0,
+            malformed_addresses: 0,
+        });
+
+        let report = build_audit_report(&project.ctx(), Some(1), None, None, false).unwrap();
+        assert!(report.chains_below_floor.is_empty());
+    }
+
+    #[test]
+    fn build_audit_report_compares_expected_chains() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", Some(r#"{"TokenModule#Token": "0x1234567890123456789012345678901234567890"}"#));
+        project.deployment("chain-84532", None);
+        project.deployment("chain-31337", Some(r#"{"TokenModule#Token": "0x1234567890123456789012345678901234567890"}"#));
+
+        let chains = project.root().join("chains.txt");
+        fs::write(&chains, "# mainnets\n8453\n10\n\n84532\n").unwrap();
+        let expected = load_chain_ids(&chains).unwrap();
+        assert_eq!(expected, [8453, 10, 84532]);
+
+        let report = build_audit_report(&project.ctx(), None, None, Some(&expected), false).unwrap();
+        assert_eq!(report.expected_without_deployment, [10, 84532]);
+        assert_eq!(report.deployment_not_expected, [31337]);
+
+        fs::write(&chains, "[8453, 31337]").unwrap();
+        assert_eq!(load_chain_ids(&chains).unwrap(), [8453, 31337]);
+        fs::write(&chains, "8453\nbase\n").unwrap();
+        assert!(load_chain_ids(&chains).is_err());
+    }
+
+    #[test]
+    fn project_root_accepts_config_file() {
+        let project = Project::new(CONFIG);
+        let config_path = project.root().join("hardhat.config.ts");
+
+        assert_eq!(config::project_root(project.root()).unwrap(), (project.root().to_path_buf(), None));
+        assert_eq!(config::project_root(&config_path).unwrap(), (project.root().to_path_buf(), Some(config_path)));
+
+        let other = project.root().join("README.md");
+        fs::write(&other, "").unwrap();
+        assert!(config::project_root(&other).unwrap_err().contains("is a file"));
+    }
+
+    #[test]
+    fn load_alias_map_prefers_network_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("aliases.toml");
+        fs::write(&path, "\"V2Final#TokenProxy\" = \"Token\"\n\n[base]\n\"V2Final#TokenProxy\" = \"Token (Base)\"\n").unwrap();
+
+        let aliases = load_alias_map(&path).unwrap();
+        assert_eq!(aliases.get("base", "V2Final#TokenProxy"), Some("Token (Base)"));
+        assert_eq!(aliases.get("ethereum", "V2Final#TokenProxy"), Some("Token"));
+        assert_eq!(aliases.get("ethereum", "Vault"), None);
+
+        fs::write(&path, "Token = 1\n").unwrap();
+        assert!(load_alias_map(&path).is_err());
+    }
+
+    #[test]
+    fn parse_age_units() {
+        assert_eq!(parse_age("90d").unwrap(), Duration::from_secs(90 * 24 * 60 * 60));
+        assert_eq!(parse_age("2w").unwrap(), Duration::from_secs(14 * 24 * 60 * 60));
+        assert_eq!(parse_age("30s").unwrap(), Duration::from_secs(30));
+        assert!(parse_age("90").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("1y").is_err());
+    }
+
+    #[test]
+    fn find_deployments_older_than_uses_mtime() {
+        let project = Project::new(CONFIG);
+        let old = project.deployment("chain-8453", Some(r#"{"Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"}"#));
+        project.deployment("chain-84532", Some(r#"{"Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"}"#));
+        let modified = SystemTime::now() - Duration::from_secs(100 * 24 * 60 * 60);
+        fs::File::options().write(true).open(old.join("deployed_addresses.json")).unwrap()
+            .set_modified(modified).unwrap();
+
+        let found = find_deployments_older_than(&project.ctx(), parse_age("90d").unwrap()).unwrap();
+        assert_eq!(found, vec![OldDeployment { directory: "chain-8453".to_string(), chain_id: 8453, age_days: 100 }]);
+        assert_eq!(build_audit_report(&project.ctx(), None, Some(parse_age("90d").unwrap()), None, false).unwrap().old_deployments, found);
+    }
+
+    #[test]
+    fn build_list_report_at_git_ref() {
+        let project = Project::new(CONFIG);
+        project.git(&["init", "-q"]);
+        project.deployment("chain-8453", Some(r#"{"TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"}"#));
+        project.commit("v1");
+        project.git(&["tag", "v1"]);
+        project.deployment("chain-8453", Some(r#"{"TokenModule#Token": "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512"}"#));
+        project.deployment("chain-84532", Some(r#"{"TokenModule#Token": "0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0"}"#));
+
+        let report = build_list_report_at(&project.ctx(), ContractSelection::First, Some("v1")).unwrap();
+        assert_eq!(report.deployments.len(), 1);
+        assert_eq!(report.deployments[0].contracts[0].address, "0x5FbDB2315678afecb367f032d93F642f64180aa3");
+        assert_eq!(report.missing.len(), 1);
+
+        let err = build_list_report_at(&project.ctx(), ContractSelection::First, Some("v2")).unwrap_err();
+        assert_eq!(err, "Unknown git ref v2");
+    }
+
+    #[test]
+    fn contract_history_lists_address_changes() {
+        let project = Project::new(CONFIG);
+        project.git(&["init", "-q"]);
+        project.deployment("chain-8453", Some(r#"{"TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"}"#));
+        project.commit("deploy");
+        project.deployment("chain-8453", Some(r#"{
+            "TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+            "VaultModule#Vault": "0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0"
+        }"#));
+        project.commit("add vault");
+        project.deployment("chain-8453", Some(r#"{"TokenModule#Token": "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512"}"#));
+        project.commit("redeploy");
+
+        let history = contract_history(&project.ctx(), "base", "Token").unwrap();
+        let addresses: Vec<Option<&str>> = history.iter().map(|entry| entry.address.as_deref()).collect();
+        assert_eq!(addresses, vec![
+            Some("0x5FbDB2315678afecb367f032d93F642f64180aa3"),
+            Some("0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512"),
+        ]);
+
+        let history = contract_history(&project.ctx(), "base", "Vault").unwrap();
+        let addresses: Vec<Option<&str>> = history.iter().map(|entry| entry.address.as_deref()).collect();
+        assert_eq!(addresses, vec![Some("0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0"), None]);
+
+        let err = contract_history(&project.ctx(), "base", "Pool").unwrap_err();
+        assert_eq!(err, "Contract Pool not found in the git history of network base");
+    }
+
+    #[test]
+    fn build_list_report_counts_configured_networks() {
+        let project = Project::new(CONFIG);
+        assert_eq!(build_list_report(&project.ctx(), ContractSelection::First).unwrap().configured_networks, 2);
+
+        let project = Project::new("const config = { networks: { hardhat: {} } };");
+        let report = build_list_report(&project.ctx(), ContractSelection::First).unwrap();
+        assert_eq!(report.configured_networks, 0);
+        assert!(report.deployments.is_empty() && report.missing.is_empty());
+    }
+
+    #[test]
+    fn build_list_report_keeps_file_order() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", Some(r#"{
+            "VaultModule#Vault": "0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0",
+            "TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"
+        }"#));
+
+        let mut report = build_list_report(&project.ctx(), ContractSelection::All).unwrap();
+        let names = |report: &ListReport| -> Vec<String> {
+            report.deployments[0].contracts.iter().map(|contract| contract.name.clone()).collect()
+        };
+        assert_eq!(names(&report), vec!["VaultModule#Vault", "TokenModule#Token"]);
+
+        report.sort_contracts();
+        assert_eq!(names(&report), vec!["TokenModule#Token", "VaultModule#Vault"]);
+
+        // A repeated key keeps its first position and its last address
+        let contracts = parse_deployed_addresses(r#"{"B#B": "0x1", "A#A": "0x2", "B#B": "0x3"}"#).unwrap().unwrap();
+        assert_eq!(contracts, [("B#B".to_string(), "0x3".to_string()), ("A#A".to_string(), "0x2".to_string())]);
+    }
+
+    #[test]
+    fn build_selected_report_filters_and_names_contracts() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", Some(r#"{
+            "VaultModule#Vault": "0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0",
+            "TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"
+        }"#));
+        project.deployment("chain-84532", Some(r#"{"TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"}"#));
+        let networks = ["base".to_string(), "optimism".to_string()];
+
+        let report = build_selected_report(&project.ctx(), &ListSelection {
+            contract_pattern: Some("Token$"),
+            networks: &networks,
+            ..ListSelection::default()
+        }).unwrap();
+        assert_eq!(report.deployments.len(), 1);
+        assert_eq!(report.deployments[0].contracts[0].name, "TokenModule#Token");
+        assert_eq!(report.warnings, ["Network optimism not found"]);
+
+        let excluded = ["base".to_string()];
+        let report = build_selected_report(&project.ctx(), &ListSelection {
+            all_contracts: true,
+            sort_contracts: true,
+            exclude_prefixes: &excluded,
+            ..ListSelection::default()
+        }).unwrap();
+        assert!(report.deployments.is_empty() && report.missing.is_empty());
+
+        let err = build_selected_report(&Project::new("export default {};").ctx(), &ListSelection {
+            strict_config: true,
+            ..ListSelection::default()
+        }).unwrap_err();
+        assert!(err.contains("No networks with a chainId"), "{}", err);
+    }
+
+    #[test]
+    fn group_by_ecosystem_sorts_groups() {
+        let networks = ["optimism", "baseSepolia", "base", "optimismSepolia", "arbitrum"];
+        let prefixes = |groups: &[(String, Vec<(String, &str)>)]| -> Vec<String> {
+            groups.iter().map(|(prefix, _)| prefix.clone()).collect()
+        };
+
+        let groups = group_by_ecosystem(networks, |network| network, "name", |_| None);
+        assert_eq!(prefixes(&groups), ["arbitrum", "base", "optimism"]);
+        assert_eq!(groups[1].1, [("Sepolia".to_string(), "baseSepolia"), ("Mainnet".to_string(), "base")]);
+
+        let groups = group_by_ecosystem(networks, |network| network, "count", |_| None);
+        assert_eq!(prefixes(&groups), ["base", "optimism", "arbitrum"]);
+
+        let deployed_at = |network: &&str| (*network == "optimismSepolia").then_some(SystemTime::UNIX_EPOCH);
+        let groups = group_by_ecosystem(networks, |network| network, "first-deployed", deployed_at);
+        assert_eq!(prefixes(&groups), ["optimism", "arbitrum", "base"]);
+    }
+
+    #[test]
+    fn list_report_add_tags() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", Some(r#"{
+            "TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+            "TokenModule#Vault": "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512"
+        }"#));
+        fs::write(project.root().join("deployments-meta.toml"), r#"
+[base]
+Token = ["audited", "owner:multisig"]
+"TokenModule#Vault" = ["frozen"]
+
+[baseSepolia]
+Token = ["testnet"]
+"#).unwrap();
+
+        let mut report = build_list_report(&project.ctx(), ContractSelection::All).unwrap();
+        report.add_tags(&load_deployment_tags(&project.ctx()).unwrap());
+        let tags: Vec<&[String]> = report.deployments[0].contracts.iter()
+            .map(|contract| contract.tags.as_slice())
+            .collect();
+
+        assert_eq!(tags, vec![&["audited".to_string(), "owner:multisig".to_string()][..], &["frozen".to_string()][..]]);
+        assert!(load_deployment_tags(&Project::new(CONFIG).ctx()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn build_deployment_scan_report_ignores_config() {
+        let project = Project::new("this isn't a hardhat config");
+        project.deployment("chain-10", Some(r#"{"TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"}"#));
+        project.deployment("chain-999111", Some(r#"{"TokenModule#Token": "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512"}"#));
+        project.deployment("chain-8453", None);
+        project.deployment("chain-1", None);
+
+        let mut ctx = project.ctx();
+        ctx.ignore_dirs = vec!["chain-1".to_string()];
+        let report = build_deployment_scan_report(&ctx, ContractSelection::First).unwrap();
+        let networks: Vec<(&str, u64)> = report.deployments.iter()
+            .map(|deployment| (deployment.network.as_str(), deployment.chain_id))
+            .collect();
+
+        assert_eq!(networks, vec![("OP Mainnet", 10), ("chain-999111", 999111)]);
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.missing[0].network, "Base");
+    }
+
+    #[test]
+    fn list_report_add_aliases() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", Some(r#"{"TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"}"#));
+        let aliases: Aliases = serde_json::from_str(r#"{
+            "base": {"Multicall3": "0xcA11bde05977b3631167028862bE2a173976CA11"},
+            "baseSepolia": {"Multicall3": "0xcA11bde05977b3631167028862bE2a173976CA11"},
+            "optimism": {"Multicall3": "0xcA11bde05977b3631167028862bE2a173976CA11"}
+        }"#).unwrap();
+
+        let mut report = build_list_report(&project.ctx(), ContractSelection::All).unwrap();
+        report.add_aliases(&aliases);
+        let contracts: Vec<(&str, String)> = report.deployments.iter()
+            .flat_map(|deployment| deployment.contracts.iter().map(|contract| (deployment.network.as_str(), contract.display_name())))
+            .collect();
+
+        assert_eq!(contracts, vec![
+            ("base", "TokenModule#Token".to_string()),
+            ("base", "Multicall3 (external)".to_string()),
+            ("baseSepolia", "Multicall3 (external)".to_string()),
+        ]);
+        assert!(report.missing.is_empty());
+        assert_eq!(report.warnings, vec!["Aliases given for unconfigured network optimism"]);
+    }
+
+    #[test]
+    fn diff_contract_addresses_between_refs() {
+        let project = Project::new(CONFIG);
+        project.git(&["init", "-q"]);
+        project.deployment("chain-8453", Some(r#"{"TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"}"#));
+        project.commit("v1");
+        project.git(&["tag", "v1"]);
+        project.deployment("chain-8453", Some(r#"{"TokenModule#Token": "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512"}"#));
+        project.deployment("chain-84532", Some(r#"{"TokenModule#Token": "0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0"}"#));
+        project.commit("v2");
+
+        let changes = diff_contract_addresses(&project.ctx(), "Token", "v1", "HEAD").unwrap();
+        let kinds: Vec<(&str, AddressChangeKind)> = changes.iter()
+            .map(|change| (change.network.as_str(), change.change))
+            .collect();
+        assert_eq!(kinds, vec![("base", AddressChangeKind::Changed), ("baseSepolia", AddressChangeKind::Added)]);
+        assert_eq!(changes[0].from.as_deref(), Some("0x5FbDB2315678afecb367f032d93F642f64180aa3"));
+
+        let changes = diff_contract_addresses(&project.ctx(), "Token", "HEAD", "v1").unwrap();
+        assert_eq!(changes[1].change, AddressChangeKind::Removed);
+        assert!(diff_contract_addresses(&project.ctx(), "Vault", "v1", "HEAD").unwrap().is_empty());
+    }
+
+    #[test]
+    fn locate_contract_finds_source_and_artifact() {
+        let project = Project::new(CONFIG);
+        let chain_dir = project.deployment("chain-8453", Some(r#"{"TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3", "TokenModule#Vault": "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512"}"#));
+        fs::create_dir_all(chain_dir.join("artifacts")).unwrap();
+        fs::create_dir_all(chain_dir.join("build-info")).unwrap();
+        fs::write(chain_dir.join("artifacts/TokenModule#Token.json"), "{}").unwrap();
+        fs::write(chain_dir.join("artifacts/TokenModule#Token.dbg.json"), r#"{"buildInfo": "../build-info/abc.json"}"#).unwrap();
+        fs::write(chain_dir.join("build-info/abc.json"), r#"{"output": {"contracts": {"contracts/Token.sol": {"Token": {}}}}}"#).unwrap();
+
+        let location = locate_contract(&project.ctx(), "base", Some("Token")).unwrap();
+        assert_eq!(location.contract, "TokenModule#Token");
+        assert_eq!(location.source, Some(project.root().join("contracts/Token.sol")));
+        assert_eq!(location.artifact, Some(chain_dir.join("artifacts/TokenModule#Token.json")));
+
+        let location = locate_contract(&project.ctx(), "base", Some("Vault")).unwrap();
+        assert_eq!((location.source, location.artifact), (None, None));
+        assert!(locate_contract(&project.ctx(), "baseSepolia", None).is_err());
+    }
+
+    #[test]
+    fn explain_missing_deployment_reasons() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", None);
+        project.deployment("chain-84532", Some("{}"));
+        project.deployment("chain-1", Some(r#"{"TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"}"#));
+
+        assert_eq!(explain_missing_deployment(&project.ctx(), 10).unwrap(), MissingReason::NoDirectory);
+        assert_eq!(explain_missing_deployment(&project.ctx(), 8453).unwrap(), MissingReason::NoAddressesFile);
+        assert_eq!(explain_missing_deployment(&project.ctx(), 84532).unwrap(), MissingReason::EmptyAddresses);
+        assert_eq!(explain_missing_deployment(&project.ctx(), 1).unwrap(), MissingReason::NoMatchingContracts);
+    }
+
+    #[test]
+    fn compare_config_networks_reports_changes() {
+        let project = Project::new(CONFIG);
+        let other = project.root().join("old.config.ts");
+        fs::write(&other, r#"
+const config: HardhatUserConfig = {
+  networks: {
+    hardhat: {},
+    base: { chainId: 8453 },
+    baseSepolia: { chainId: 84531 },
+    ethereum: { chainId: 1 },
+  },
+};
+"#).unwrap();
+
+        let changes = compare_config_networks(&project.ctx(), &other).unwrap();
+        let kinds: Vec<(&str, NetworkChangeKind)> = changes.iter()
+            .map(|change| (change.network.as_str(), change.change))
+            .collect();
+        assert_eq!(kinds, vec![("baseSepolia", NetworkChangeKind::ChainIdChanged), ("ethereum", NetworkChangeKind::Removed)]);
+        assert_eq!((changes[0].from, changes[0].to), (Some(84531), Some(84532)));
+        assert!(compare_config_networks(&project.ctx(), &project.root().join("hardhat.config.ts")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn retain_changed_since_keeps_changed_deployments() {
+        let project = Project::new(CONFIG);
+        project.git(&["init", "-q"]);
+        project.deployment("chain-8453", Some(r#"{"TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"}"#));
+        project.deployment("chain-84532", Some(r#"{"TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"}"#));
+        project.commit("v1");
+        project.git(&["tag", "v1"]);
+        project.deployment("chain-84532", Some(r#"{"TokenModule#Token": "0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0"}"#));
+        project.commit("v2");
+
+        let mut report = build_list_report(&project.ctx(), ContractSelection::All).unwrap();
+        report.retain_changed_since(&project.ctx(), "v1").unwrap();
+        assert_eq!(report.deployments.len(), 1);
+        assert_eq!(report.deployments[0].network, "baseSepolia");
+        assert!(report.deployments[0].commit.is_some());
+
+        let mut report = build_list_report(&project.ctx(), ContractSelection::All).unwrap();
+        report.retain_changed_since(&project.ctx(), "HEAD").unwrap();
+        assert!(report.deployments.is_empty());
+    }
+
+    #[test]
+    fn checksum_address_matches_eip55() {
+        for address in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            assert_eq!(checksum_address(&address.to_lowercase()).as_deref(), Some(address));
+            assert!(is_checksummed_address(address));
+        }
+        assert!(!is_checksummed_address("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"));
+        assert!(!is_checksummed_address("0x5AAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"));
+        assert_eq!(checksum_address("0x1234"), None);
+    }
+
+    #[test]
+    fn code_hash_is_keccak256() {
+        assert_eq!(
+            code_hash("0x").as_deref(),
+            Some("0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+        );
+        assert_eq!(code_hash("0x123"), None);
+    }
+
+    #[test]
+    fn build_audit_report_checksum_inconsistencies() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", Some(r#"{
+            "TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+            "TokenModule#Vault": "0xe7f1725e7734ce288f8367e1bb143e90bb3f0512"
+        }"#));
+        project.deployment("chain-84532", Some(r#"{"TokenModule#Token": "0x5fbdb2315678afecb367f032d93f642f64180aa3"}"#));
+
+        let report = build_audit_report(&project.ctx(), None, None, None, false).unwrap();
+        assert_eq!(report.checksum_inconsistencies, vec![ChecksumInconsistency {
+            directory: "chain-8453".to_string(),
+            chain_id: 8453,
+            non_checksummed: vec!["TokenModule#Vault".to_string()],
+        }]);
+    }
+
+    #[test]
+    fn build_resolver_report_reads_command_output() {
+        let project = Project::new(CONFIG);
+        let script = project.root().join("resolve.sh");
+        fs::write(&script, r#"echo '{"base": "0x5FbDB2315678afecb367f032d93F642f64180aa3", "optimism": "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512"}'"#).unwrap();
+
+        let report = build_resolver_report(&project.ctx(), "sh", &[script.display().to_string()]).unwrap();
+        assert_eq!(report.deployments.len(), 1);
+        assert_eq!(report.deployments[0].network, "base");
+        assert_eq!(report.deployments[0].contracts[0].address, "0x5FbDB2315678afecb367f032d93F642f64180aa3");
+        assert_eq!(report.missing[0].network, "baseSepolia");
+        assert_eq!(report.warnings, vec!["Resolver returned an address for unconfigured network optimism"]);
+
+        fs::write(&script, r#"echo '{"base": 8453}'"#).unwrap();
+        let err = build_resolver_report(&project.ctx(), "sh", &[script.display().to_string()]).unwrap_err();
+        assert_eq!(err, "Resolver output maps network base to 8453, which isn't an address");
+
+        // Paths and arguments with spaces are passed through as they are
+        let dir = project.root().join("my scripts");
+        fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("my resolver.sh");
+        fs::write(&script, "#!/bin/sh\n[ \"$1\" = \"two words\" ] && echo '{\"base\": \"0x5FbDB2315678afecb367f032d93F642f64180aa3\"}'\n").unwrap();
+        fs::set_permissions(&script, std::os::unix::fs::PermissionsExt::from_mode(0o755)).unwrap();
+        let report = build_resolver_report(&project.ctx(), &script.display().to_string(), &["two words".to_string()]).unwrap();
+        assert_eq!(report.deployments[0].network, "base");
+    }
+
+    #[test]
+    fn get_deployment_address_reads_address() {
+        let project = Project::new(CONFIG);
+        let dir = project.deployment("chain-8453", Some(r#"{"TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"}"#));
+
+        assert_eq!(
+            get_deployment_address(&project.ctx(), &dir).unwrap().as_deref(),
+            Some("0x5FbDB2315678afecb367f032d93F642f64180aa3")
+        );
+    }
+
+    #[test]
+    fn get_deployment_address_missing_dir() {
+        let project = Project::new(CONFIG);
+        let dir = project.root().join("deployments").join("chain-8453");
+        assert_eq!(get_deployment_address(&project.ctx(), &dir).unwrap(), None);
+    }
+
+    #[test]
+    fn get_deployment_address_missing_file() {
+        let project = Project::new(CONFIG);
+        let dir = project.deployment("chain-8453", None);
+        assert_eq!(get_deployment_address(&project.ctx(), &dir).unwrap(), None);
+    }
+
+    #[test]
+    fn get_deployment_address_empty_json() {
+        let project = Project::new(CONFIG);
+        let dir = project.deployment("chain-8453", Some("{}"));
+        assert_eq!(get_deployment_address(&project.ctx(), &dir).unwrap(), None);
+    }
+
+    #[test]
+    fn get_deployment_address_empty_file() {
+        let project = Project::new(CONFIG);
+        let dir = project.deployment("chain-8453", Some(""));
+        let err = get_deployment_address(&project.ctx(), &dir).unwrap_err();
+        assert!(err.starts_with("Failed to parse deployed_addresses.json"));
+    }
+
+    #[test]
+    fn get_deployment_address_malformed_json() {
+        let project = Project::new(CONFIG);
+        let dir = project.deployment("chain-8453", Some(r#"{"TokenModule#Token": "0x5FbDB"#));
+        let err = get_deployment_address(&project.ctx(), &dir).unwrap_err();
+        assert!(err.starts_with("Failed to parse deployed_addresses.json"));
+    }
+
+    #[test]
+    fn get_deployment_contracts_reads_string_and_object_values() {
+        let project = Project::new(CONFIG);
+        let dir = project.deployment("chain-8453", Some(r#"{
+            "TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+            "TokenModule#Vault": {
+                "address": "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512",
+                "abi": []
+            }
+        }"#));
+
+        assert_eq!(get_deployment_contracts(&project.ctx(), &dir).unwrap(), Some(vec![
+            ("TokenModule#Token".to_string(), "0x5FbDB2315678afecb367f032d93F642f64180aa3".to_string()),
+            ("TokenModule#Vault".to_string(), "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512".to_string()),
+        ]));
+    }
+
+    #[test]
+    fn get_deployment_address_reads_object_value() {
+        let project = Project::new(CONFIG);
+        let dir = project.deployment("chain-8453", Some(r#"{"Token": {"address": "0x5FbDB2315678afecb367f032d93F642f64180aa3", "abi": []}}"#));
+
+        assert_eq!(
+            get_deployment_address(&project.ctx(), &dir).unwrap().as_deref(),
+            Some("0x5FbDB2315678afecb367f032d93F642f64180aa3")
+        );
+    }
+
+    #[test]
+    fn get_deployment_address_strips_bom() {
+        let project = Project::new(&format!("\u{feff}{}", CONFIG));
+        let dir = project.deployment("chain-8453", Some("\u{feff}{\"TokenModule#Token\": \"0x5FbDB2315678afecb367f032d93F642f64180aa3\"}"));
+
+        assert_eq!(
+            get_deployment_address(&project.ctx(), &dir).unwrap().as_deref(),
+            Some("0x5FbDB2315678afecb367f032d93F642f64180aa3")
+        );
+        assert_eq!(parse_hardhat_config(&project.ctx()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn get_deployment_address_ignores_objects_without_address() {
+        let project = Project::new(CONFIG);
+        let dir = project.deployment("chain-8453", Some(r#"{"Token": {"abi": []}}"#));
+        assert_eq!(get_deployment_address(&project.ctx(), &dir).unwrap(), None);
+    }
+
+    #[test]
+    fn get_deployment_address_ignores_non_string_values() {
+        let project = Project::new(CONFIG);
+        let dir = project.deployment("chain-8453", Some(r#"{"TokenModule#Token": 42}"#));
+        assert_eq!(get_deployment_address(&project.ctx(), &dir).unwrap(), None);
+    }
+
+    #[test]
+    fn load_contract_names_reads_lines_and_toml_arrays() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("contracts.txt");
+
+        fs::write(&path, "# stakeholders\nToken\n\nVaultModule#Vault\n").unwrap();
+        assert_eq!(load_contract_names(&path).unwrap(), ["Token", "VaultModule#Vault"]);
+
+        fs::write(&path, "[\"Token\", \"Vault\"]\n").unwrap();
+        assert_eq!(load_contract_names(&path).unwrap(), ["Token", "Vault"]);
+
+        fs::write(&path, "contracts = [\"Token\"]\n").unwrap();
+        assert_eq!(load_contract_names(&path).unwrap(), ["Token"]);
+    }
+
+    #[test]
+    fn json_to_string_pretty_uses_given_indent() {
+        let value = serde_json::json!({ "chainIds": [8453], "network": "base" });
+        let output = json::to_string_pretty(&value, 4).unwrap();
+        assert_eq!(output, "{\n    \"chainIds\": [\n        8453\n    ],\n    \"network\": \"base\"\n}");
+    }
+
+    #[test]
+    fn json_append_to_array_accumulates_and_dedupes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out").join("records.json");
+        let base = serde_json::json!({ "network": "base", "address": "0x5FbDB2315678afecb367f032d93F642f64180aa3" });
+        let optimism = serde_json::json!({ "network": "optimism", "address": "0x5FbDB2315678afecb367f032d93F642f64180aa3" });
+
+        assert_eq!(json::append_to_array(&path, vec![base.clone()], false, json::DEFAULT_INDENT).unwrap(), 1);
+        assert_eq!(json::append_to_array(&path, vec![base.clone(), optimism.clone()], true, json::DEFAULT_INDENT).unwrap(), 1);
+        assert_eq!(json::append_to_array(&path, vec![base.clone()], false, json::DEFAULT_INDENT).unwrap(), 1);
+        let array: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(array, serde_json::json!([base, optimism, base]));
+        assert!(!dir.path().join("out").join(".records.json.tmp").exists());
+
+        fs::write(&path, "{}").unwrap();
+        assert!(json::append_to_array(&path, Vec::new(), false, json::DEFAULT_INDENT).unwrap_err().contains("doesn't contain a JSON array"));
+    }
+
+    #[test]
+    fn registry_parses_network_name_overrides() {
+        assert_eq!(registry::parse_override("8453=Base Prod").unwrap(), (8453, "Base Prod".to_string()));
+        assert!(registry::parse_override("base=Base Prod").is_err());
+        assert!(registry::parse_override("8453=").is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("names.txt");
+        fs::write(&path, "# internal chains\n8453 = Base Prod\n\n123456=Devnet\n").unwrap();
+        assert_eq!(registry::load_overrides(&path).unwrap(), [(8453, "Base Prod".to_string()), (123456, "Devnet".to_string())]);
+    }
+
+    #[test]
+    fn context_reads_projects_from_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snapshot.tar.gz");
+        let mut tar = tar::Builder::new(flate2::write::GzEncoder::new(fs::File::create(&path).unwrap(), flate2::Compression::default()));
+        let addresses = r#"{"TokenModule#Token": "0x1234567890123456789012345678901234567890"}"#;
+        for (name, content) in [
+            ("snapshot/hardhat.config.ts", CONFIG),
+            ("snapshot/deployments/chain-8453/deployed_addresses.json", addresses),
+            ("snapshot/deployments/chain-84532/deployed_addresses.json", addresses),
+            ("snapshot/deployments/.backup/deployed_addresses.json", addresses),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append_data(&mut header, name, content.as_bytes()).unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap();
+
+        let archive = source::Archive::open(&path).unwrap();
+        assert_eq!(archive.read_file("hardhat.config.ts").unwrap().as_deref(), Some(CONFIG));
+
+        let mut ctx = Context::new(&path);
+        ctx.source = Box::new(archive);
+        ctx.config = config::Source::Content { name: "hardhat.config.ts".to_string(), content: CONFIG.to_string() };
+        assert_eq!(count_deployments(&ctx).unwrap(), 2);
+        assert_eq!(get_deployment_address(&ctx, &ctx.root.join("deployments/chain-8453")).unwrap().as_deref(), Some("0x1234567890123456789012345678901234567890"));
+
+        ctx.set_chain_globs(&["chain-8453".to_string()]).unwrap();
+        assert_eq!(count_deployments(&ctx).unwrap(), 1);
+        let report = build_list_report(&ctx, ContractSelection::First).unwrap();
+        assert_eq!(report.deployments.len(), 1);
+        assert_eq!(report.deployments[0].contracts[0].address, "0x1234567890123456789012345678901234567890");
+        assert!(report.missing.is_empty());
+        assert!(ctx.require_working_tree("history").is_err());
+    }
+
+    #[test]
+    fn find_selector_collisions_reports_shared_selectors() {
+        let project = Project::new(CONFIG);
+        let dir = project.deployment("chain-8453", Some(r#"{
+            "DiamondModule#BurnFacet": "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+            "DiamondModule#StorageFacet": {"address": "0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0", "abi": [
+                {"type": "function", "name": "collate_propagate_storage", "inputs": [{"name": "", "type": "bytes16"}]}
+            ]}
+        }"#));
+        fs::create_dir_all(dir.join("artifacts")).unwrap();
+        fs::write(dir.join("artifacts/DiamondModule#BurnFacet.json"), r#"{"abi": [
+            {"type": "function", "name": "burn", "inputs": [{"name": "amount", "type": "uint256"}]},
+            {"type": "function", "name": "settle", "inputs": [{"name": "orders", "type": "tuple[]", "components": [{"name": "id", "type": "uint256"}, {"name": "to", "type": "address"}]}]}
+        ]}"#).unwrap();
+
+        assert_eq!(function_signature(&load_contract_abi(&project.ctx(), &dir, "DiamondModule#BurnFacet").unwrap()[1]).as_deref(), Some("settle((uint256,address)[])"));
+        assert_eq!(function_selector("transfer(address,uint256)"), "0xa9059cbb");
+
+        let collisions = find_selector_collisions(&project.ctx()).unwrap();
+        assert_eq!(collisions, [SelectorCollision {
+            directory: "chain-8453".to_string(),
+            chain_id: 8453,
+            selector: "0x42966c68".to_string(),
+            functions: vec![
+                SelectorFunction { contract: "DiamondModule#BurnFacet".to_string(), signature: "burn(uint256)".to_string() },
+                SelectorFunction { contract: "DiamondModule#StorageFacet".to_string(), signature: "collate_propagate_storage(bytes16)".to_string() },
+            ],
+        }]);
+    }
+
+    #[test]
+    fn list_report_counts_networks_and_contracts() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", Some(r#"{"TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3", "VaultModule#Vault": "0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0"}"#));
+        project.deployment("chain-1", Some(r#"{"TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"}"#));
+
+        let report = build_list_report(&project.ctx(), ContractSelection::All).unwrap();
+        assert_eq!(report.counts(), DeploymentCounts { configured_networks: 2, deployed_networks: 1, total_contracts: 2 });
+        assert_eq!(count_deployments(&project.ctx()).unwrap(), 2);
+    }
+
+    /// Serves one JSON-RPC `result` per connection from `results`, or drops
+    /// the connection without a response for `None`, and returns the URL.
+    fn rpc_node(results: Vec<Option<&'static str>>) -> String {
+        use std::io::{BufRead, BufReader, Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for result in results {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(length) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        content_length = length.trim().parse().unwrap();
+                    }
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                }
+                reader.read_exact(&mut vec![0; content_length]).unwrap();
+                if let Some(result) = result {
+                    let body = format!(r#"{{"jsonrpc": "2.0", "id": 1, "result": {}}}"#, result);
+                    let response = format!("HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body);
+                    reader.get_mut().write_all(response.as_bytes()).unwrap();
+                }
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn verify_code_hashes_reports_rpc_errors_apart_from_missing_code() {
+        let project = Project::new(CONFIG);
+        project.deployment("chain-8453", Some(r#"{
+            "TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+            "VaultModule#Vault": "0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0"
+        }"#));
+        let expected = BTreeMap::from([
+            ("Token".to_string(), code_hash("0x6000").unwrap()),
+            ("Vault".to_string(), code_hash("0x6000").unwrap()),
+        ]);
+
+        // The Token lookup succeeds on its retry, the Vault one never does
+        let rpc = rpc::RpcClient::new(&rpc_node(vec![None, Some(r#""0x""#), None, None])).unwrap()
+            .with_timeout(Duration::from_secs(5))
+            .with_retries(1);
+        let checks = verify_code_hashes(&project.ctx(), "base", &rpc, &expected, false, false).unwrap();
+        let statuses: Vec<(&str, CodeHashStatus)> = checks.iter().map(|check| (check.contract.as_str(), check.status)).collect();
+        assert_eq!(statuses, vec![("TokenModule#Token", CodeHashStatus::NoCode), ("VaultModule#Vault", CodeHashStatus::RpcError)]);
+        assert!(checks[0].error.is_none());
+        assert!(checks[1].error.as_deref().unwrap().starts_with("RPC request failed"));
+    }
+
+    #[test]
+    fn deployment_block_time_reads_the_journal_transaction() {
+        let project = Project::new(CONFIG);
+        let dir = project.deployment("chain-8453", Some(r#"{"TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3", "VaultModule#Vault": "0x9fE46736679d2D9a65F0992F2272dE9f3c7fa6e0"}"#));
+        fs::write(dir.join("journal.jsonl"), [
+            r#"{"chainId": 8453, "type": "DEPLOYMENT_INITIALIZE"}"#,
+            r#"{"futureId": "TokenModule#Token", "networkInteractionId": 1, "transaction": {"hash": "0x01"}, "type": "TRANSACTION_SEND"}"#,
+            r#"{"futureId": "TokenModule#Token", "hash": "0x01", "networkInteractionId": 1, "receipt": {"blockNumber": 16, "status": "SUCCESS"}, "type": "TRANSACTION_CONFIRM"}"#,
+            r#"{"futureId": "VaultModule#Vault", "networkInteractionId": 1, "transaction": {"hash": "0x02"}, "type": "TRANSACTION_SEND"}"#,
+        ].join("\n")).unwrap();
+
+        let ctx = project.ctx();
+        assert_eq!(deployment_transaction(&ctx, &dir, "TokenModule#Token"), Some(("0x01".to_string(), Some(16))));
+        assert_eq!(deployment_transaction(&ctx, &dir, "VaultModule#Vault"), Some(("0x02".to_string(), None)));
+        assert_eq!(deployment_transaction(&ctx, &dir, "PoolModule#Pool"), None);
+
+        // The Vault's block isn't in the journal, so it's looked up first
+        let rpc = rpc::RpcClient::new(&rpc_node(vec![Some(r#"{"blockNumber": "0x11"}"#), Some(r#"{"timestamp": "0x6632300"}"#)])).unwrap();
+        assert_eq!(deployment_block_time(&ctx, &dir, &rpc, "VaultModule#Vault").unwrap(), Some(0x6632300));
+        assert_eq!(deployment_block_time(&ctx, &dir, &rpc, "PoolModule#Pool").unwrap(), None);
+    }
+}
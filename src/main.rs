@@ -1,18 +1,35 @@
 use clap::{Parser, Subcommand};
+use evm_deployment_info::{
+    camel_to_title_case, compare_suffixes, config, count_deployments, ens, explorer,
+    build_audit_report, build_selected_report, compare_config_networks, contract_history, collapse_identical_contracts, diff_contract_addresses, verify_code_hashes, build_health_report, build_list_report_at, deployments_dir, deployment_times,
+    get_deployment_address, get_deployment_contracts, group_by_ecosystem, load_chain_ids, load_contract_names, parse_network_urls, registry, require_config_networks, load_expected_code_hashes, locate_contract, json, parse_age, parse_hardhat_config, proxy_links, resolve_contract_source, resolve_deployment, rpc, select_report_fields,
+    split_contract_key, split_network_name, validate_hardhat_project, AddressChangeKind, AuditReport, CodeHashStatus, ContractSelection, DeployedContract, ProxyRole,
+    errors::{ErrorLog, ErrorMode}, timing::Timings, Context, ListReport, ListSelection, NetworkDeployment, REDACTED_ADDRESS,
+    source::{Archive, DeploymentSource},
+};
 use std::path::{Path, PathBuf};
 use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::fs;
-use regex::Regex;
-use prettytable::{Table, row};
+use prettytable::{Table, Row, Cell, row, cell};
 use std::collections::BTreeMap;
 use prettytable::format;
 use std::process::Command;
-use reqwest;
 use std::collections::HashSet;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime};
 
 const VERSION: &str = "0.1.4";
 
+/// Spaces per indentation level in JSON output, set from --json-indent
+static JSON_INDENT: AtomicUsize = AtomicUsize::new(json::DEFAULT_INDENT);
+
+/// Pretty-prints `value` as JSON with the --json-indent indentation.
+fn to_json<T: serde::Serialize + ?Sized>(value: &T) -> Result<String, String> {
+    json::to_string_pretty(value, JSON_INDENT.load(Ordering::Relaxed))
+}
+
 #[derive(Parser)]
 #[command(name = "evm-deployment-info")]
 #[command(about = "A CLI tool for analyzing hardhat deployments")]
@@ -24,42 +41,318 @@ struct Cli {
     /// Root directory of the hardhat project
     #[arg(short = 'p', long = "project", default_value = ".")]
     project: PathBuf,
+
+    /// Directory inside deployments/ to skip (can be repeated, hidden directories are always skipped)
+    #[arg(long = "ignore-dir", global = true)]
+    ignore_dirs: Vec<String>,
+
+    /// Only look at the directories inside deployments/ matching this glob (e.g. 'chain-1*'); configured networks keep their names (can be repeated)
+    #[arg(long = "chain-glob", value_name = "GLOB", global = true)]
+    chain_globs: Vec<String>,
+
+    /// Name a chain differently from the bundled registry where names are derived from chain IDs, as <chain id>=<name> (e.g. "8453=Base Prod") or a file with one such mapping per line (can be repeated)
+    #[arg(long = "network-name-map", value_name = "MAPPING|FILE", global = true)]
+    network_name_map: Vec<String>,
+
+    /// Show chain IDs with thousands separators (e.g. 11,155,111) in tables; JSON and CSV keep raw digits
+    #[arg(long = "pretty-numbers", global = true)]
+    pretty_numbers: bool,
+
+    /// Replace addresses with 0x****...**** in reports (list, audit, verify, diff-contract, history and graph), to share coverage without revealing addresses
+    #[arg(long = "redact-addresses", global = true)]
+    redact_addresses: bool,
+
+    /// What to do when a network's deployment can't be read: stop at the first error, or skip it and summarize the errors at the end
+    #[arg(long = "error-mode", global = true, default_value = "collect", value_parser = ["fail-fast", "collect"])]
+    error_mode: String,
+
+    /// Spaces per indentation level in JSON output
+    #[arg(long = "json-indent", global = true, default_value_t = json::DEFAULT_INDENT)]
+    json_indent: usize,
+
+    /// Print how long config parsing, directory scanning and rendering took to stderr
+    #[arg(long = "timing", global = true)]
+    timing: bool,
+
+    /// Skip the check that the project has a hardhat config (commands that read the config still need one)
+    #[arg(long = "no-validate", global = true)]
+    no_validate: bool,
+
+    /// Fail unless the hardhat config exists and at least one network is parsed from it, even where the command doesn't need it (e.g. for CI)
+    #[arg(long = "require-config-file", global = true, conflicts_with = "no_validate")]
+    require_config_file: bool,
+
+    /// Hardhat config to read instead of hardhat.config.ts in the project root (e.g. <project>/hardhat.config.prod.ts), or - to read it from stdin
+    #[arg(long = "config", global = true)]
+    config: Option<PathBuf>,
+
+    /// Name shown for a config read from stdin, in messages and SARIF output
+    #[arg(long = "config-stdin-name", global = true, requires = "config", default_value = config::DEFAULT_CONFIG_FILE)]
+    config_stdin_name: String,
 }
 
+// Parsed once per run, so the size of the List variant doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 enum Commands {
-    /// Count the number of deployments in the deployments directory
-    Count,
+    /// Count the configured and deployed networks and the deployed contracts
+    Count {
+        /// Output in JSON format
+        #[arg(short = 'j', long = "json")]
+        json: bool,
+        /// Only count the directories in deployments/, without reading the hardhat config
+        #[arg(long = "dirs-only")]
+        dirs_only: bool,
+    },
     /// List all deployments and their addresses
     List {
         /// Aggregate networks with common prefixes
         #[arg(short = 'a', long = "aggregate")]
         aggregate: bool,
+        /// Drop networks with the given prefix when aggregating (can be repeated)
+        #[arg(long = "exclude-prefix", requires = "aggregate")]
+        exclude_prefixes: Vec<String>,
+        /// Minimum number of networks for a prefix to be grouped when aggregating tables (smaller ecosystems are listed flat)
+        #[arg(long = "group-threshold", default_value_t = 2, requires = "aggregate")]
+        group_threshold: usize,
+        /// Order of the ecosystems when aggregating: by name, by number of networks (largest first), or by their oldest deployment
+        #[arg(long = "group-sort", default_value = "name", value_parser = ["name", "count", "first-deployed"], requires = "aggregate")]
+        group_sort: String,
         /// Output in JSON format
         #[arg(short = 'j', long = "json", conflicts_with = "csv", conflicts_with = "md", group = "output_format")]
         json: bool,
+        /// JSON schema version to output: 1 is the legacy network -> address shape, 2 lists each deployment with its chain ID and contracts
+        #[arg(long = "json-version", default_value_t = LIST_JSON_VERSION, value_parser = clap::value_parser!(u8).range(1..=LIST_JSON_VERSION as i64), requires = "json", conflicts_with = "by_contract")]
+        json_version: u8,
         /// Output in CSV format
         #[arg(short = 'c', long = "csv", conflicts_with = "json", conflicts_with = "md", group = "output_format")]
         csv: bool,
         /// Output in Markdown table format
         #[arg(short = 'm', long = "md", conflicts_with = "json", conflicts_with = "csv", group = "output_format")]
         md: bool,
-        /// Output file (only valid with --json, --csv, or --md)
+        /// Start CSV output with a UTF-8 byte order mark, so Excel detects the encoding
+        #[arg(long = "csv-bom", requires = "csv")]
+        csv_bom: bool,
+        /// Character separating CSV fields, e.g. ';' for locales that use a decimal comma
+        #[arg(long = "csv-delimiter", value_name = "CHAR", default_value_t = ',', requires = "csv")]
+        csv_delimiter: char,
+        /// Output a flat JSON array of {network, contract, address, chainId} records
+        #[arg(long = "json-array", conflicts_with_all = ["json", "csv", "md", "by_contract"], group = "output_format")]
+        json_array: bool,
+        /// Include networks without a deployment in --json-array output, with a null contract and address
+        #[arg(long = "include-missing", requires = "json_array")]
+        include_missing: bool,
+        /// Output file (only valid with --json, --json-array, --csv, or --md)
         #[arg(short = 'o', long = "outfile", requires = "output_format")]
         outfile: Option<PathBuf>,
+        /// How --json-array output is written to --outfile: replace the file, or append the records to the array already in it (e.g. to accumulate a dataset across runs)
+        #[arg(long = "output-mode", default_value = "overwrite", value_parser = ["overwrite", "append-json-array"], requires_all = ["json_array", "outfile"])]
+        output_mode: String,
+        /// Skip records already in the file (only valid with --output-mode append-json-array)
+        #[arg(long = "dedupe", requires = "output_mode")]
+        dedupe: bool,
+        /// Copy the output to the clipboard instead of printing it
+        #[arg(long = "clipboard", conflicts_with = "outfile")]
+        clipboard: bool,
+        /// Omit the header row from table and CSV output
+        #[arg(long = "no-header", visible_alias = "no-table-header")]
+        no_header: bool,
+        /// Abbreviate addresses in table output (e.g. 0x1234…abcd)
+        #[arg(long = "abbrev-addresses", overrides_with = "no_abbrev")]
+        abbrev_addresses: bool,
+        /// Always show full addresses (overrides --abbrev-addresses)
+        #[arg(long = "no-abbrev", overrides_with = "abbrev_addresses")]
+        no_abbrev: bool,
+        /// Truncate address and explorer cells so the table fits within this many columns
+        #[arg(long = "max-width", value_name = "COLS")]
+        max_width: Option<usize>,
+        /// Only show the given contracts (comma separated, e.g. Token,Vault)
+        #[arg(long = "contracts", value_delimiter = ',')]
+        contracts: Vec<String>,
+        /// Only show the contracts listed in a file, one name per line or as a TOML array (combined with --contracts)
+        #[arg(long = "contracts-file", value_name = "FILE")]
+        contracts_file: Option<PathBuf>,
+        /// Only show these networks (comma separated config names, e.g. base,baseSepolia)
+        #[arg(short = 'n', long = "network", value_delimiter = ',')]
+        networks: Vec<String>,
+        /// Only show contracts whose name or full key matches the regex (e.g. '^Vault')
+        #[arg(long = "only-contracts-matching", value_name = "REGEX", conflicts_with_all = ["contracts", "contracts_file", "primary_contract"])]
+        only_contracts_matching: Option<String>,
+        /// Contract to show for each network instead of the first one recorded, as a regex matching its name or full key (falls back to the first)
+        #[arg(long = "primary-contract", value_name = "REGEX", conflicts_with_all = ["contracts", "contracts_file", "by_contract"])]
+        primary_contract: Option<String>,
+        /// List networks sharing a contract's address in a single table row (e.g. for CREATE2 deployments)
+        #[arg(long = "collapse-identical", conflicts_with_all = ["aggregate", "by_contract", "json", "json_array", "csv", "md", "resolve_ens", "show_balances", "show_tags", "show_module", "redact_addresses"])]
+        collapse_identical: bool,
+        /// Show the native token balance of each address in ether, fetched over RPC
+        #[arg(long = "show-balances", visible_alias = "watch-rpc", conflicts_with = "redact_addresses")]
+        show_balances: bool,
+        /// RPC endpoint to fetch a network's balances from, as <network>=<url> (can be repeated; defaults to the url in the hardhat config)
+        #[arg(long = "balance-rpc", value_name = "NETWORK=URL", requires = "show_balances")]
+        balance_rpcs: Vec<String>,
+        /// Show each contract's tags from deployments-meta.toml in a column
+        #[arg(long = "show-tags")]
+        show_tags: bool,
+        /// Show the Ignition module of each contract in a column, with contract names shown without it
+        #[arg(long = "show-module", conflicts_with_all = ["json", "json_array", "csv", "md", "by_contract"])]
+        show_module: bool,
+        /// Table columns to show (comma separated: network, module, contract, address, chainid, explorer, ens, tags, balance, commit)
+        #[arg(long = "columns", value_delimiter = ',')]
+        columns: Vec<String>,
+        /// Sort the contracts of each network by name instead of the order they're recorded in
+        #[arg(long = "sort-contracts")]
+        sort_contracts: bool,
+        /// Exit with an error when a listed address is invalid or has a wrong EIP-55 checksum
+        #[arg(long = "fail-on-malformed")]
+        fail_on_malformed: bool,
+        /// Show network names as lowercase slugs (e.g. base-sepolia) in every output format
+        #[arg(long = "normalize-network-names", conflicts_with_all = ["aggregate", "by_contract"])]
+        normalize_network_names: bool,
+        /// Flag deployments whose deployed_addresses.json is older than this (e.g. 90d; units s, m, h, d, w) as potentially stale
+        #[arg(long = "max-age", value_name = "AGE", value_parser = parse_age, conflicts_with_all = ["git_ref", "resolver_cmd"])]
+        max_age: Option<Duration>,
+        /// TOML file mapping raw contract keys to display names, globally or per network table
+        #[arg(long = "alias-map", value_name = "FILE", conflicts_with = "resolver_cmd")]
+        alias_map: Option<PathBuf>,
+        /// Also use the --alias-map names in JSON output (which keeps the raw keys by default)
+        #[arg(long = "alias-json", requires = "alias_map")]
+        alias_json: bool,
+        /// Add a column to the missing networks table with the reason each one is missing
+        #[arg(long = "explain", conflicts_with_all = ["aggregate", "json", "json_array", "csv", "md", "by_contract", "git_ref", "resolver_cmd"])]
+        explain: bool,
+        /// Fields to include in JSON and CSV output (comma separated: network, contract, address, chainid)
+        #[arg(long = "fields", value_delimiter = ',', conflicts_with_all = ["aggregate", "by_contract", "md"])]
+        fields: Vec<String>,
+        /// Group deployments by contract instead of by network
+        #[arg(long = "by-contract", conflicts_with_all = ["aggregate", "columns"])]
+        by_contract: bool,
+        /// Show the ENS name of Ethereum mainnet deployments (requires --rpc-url)
+        #[arg(long = "resolve-ens", requires = "rpc_url", conflicts_with = "redact_addresses")]
+        resolve_ens: bool,
+        /// Ethereum mainnet RPC endpoint used for ENS lookups
+        #[arg(long = "rpc-url")]
+        rpc_url: Option<String>,
+        /// Read deployments as they were at this git ref (e.g. v1.2.0) instead of from the working tree
+        #[arg(long = "git-ref")]
+        git_ref: Option<String>,
+        /// Only show deployments whose deployed_addresses.json was changed by a commit after this git ref, with the latest such commit
+        #[arg(long = "since-commit", value_name = "REF", conflicts_with_all = ["git_ref", "resolver_cmd", "collapse_identical"])]
+        since_commit: Option<String>,
+        /// JSON file of extra network -> contract -> address entries to list as external contracts
+        #[arg(long = "aliases")]
+        aliases: Option<PathBuf>,
+        /// Fail instead of warning when no networks can be parsed from the config
+        #[arg(long = "strict-config")]
+        strict_config: bool,
+        /// List every chain-<id> directory without reading the hardhat config, naming chains from the bundled registry
+        #[arg(long = "deployments-only", conflicts_with_all = ["aggregate", "git_ref", "strict_config"])]
+        deployments_only: bool,
+        /// Get each network's address from this program (given the deployments directory, it must print a JSON object of network -> address)
+        #[arg(long = "resolver-cmd", value_name = "PROGRAM", conflicts_with_all = ["contracts", "contracts_file", "only_contracts_matching", "primary_contract", "by_contract", "git_ref", "deployments_only", "aliases"])]
+        resolver_cmd: Option<String>,
+        /// An argument passed to the --resolver-cmd program, before the deployments directory (repeatable)
+        #[arg(long = "resolver-arg", value_name = "ARG", allow_hyphen_values = true, requires = "resolver_cmd")]
+        resolver_args: Vec<String>,
+    },
+    /// Print the networks declared in the hardhat config, without looking at deployments
+    Networks {
+        /// Output in JSON format
+        #[arg(short = 'j', long = "json")]
+        json: bool,
     },
     /// Audit deployments and config entries
     Audit {
         /// Output in JSON format
-        #[arg(short = 'j', long = "json", conflicts_with = "csv", group = "output_format")]
+        #[arg(short = 'j', long = "json", conflicts_with_all = ["csv", "sarif"], group = "output_format")]
         json: bool,
         /// Output in CSV format
-        #[arg(short = 'c', long = "csv", conflicts_with = "json", group = "output_format")]
+        #[arg(short = 'c', long = "csv", conflicts_with_all = ["json", "sarif"], group = "output_format")]
         csv: bool,
-        /// Output file (only valid with --json or --csv)
+        /// Start CSV output with a UTF-8 byte order mark, so Excel detects the encoding
+        #[arg(long = "csv-bom", requires = "csv")]
+        csv_bom: bool,
+        /// Character separating CSV fields, e.g. ';' for locales that use a decimal comma
+        #[arg(long = "csv-delimiter", value_name = "CHAR", default_value_t = ',', requires = "csv")]
+        csv_delimiter: char,
+        /// Output findings in SARIF format (e.g. for GitHub code scanning)
+        #[arg(long = "sarif", conflicts_with_all = ["json", "csv"], group = "output_format")]
+        sarif: bool,
+        /// Only print the number of findings in each category
+        #[arg(long = "summary-only", conflicts_with_all = ["csv", "sarif"])]
+        summary_only: bool,
+        /// Fail when a deployment exists for a chain ID below this (e.g. 31338 to forbid local hardhat deployments)
+        #[arg(long = "forbid-chains-below", value_name = "CHAIN_ID")]
+        forbid_chains_below: Option<u64>,
+        /// Exit with an error when a recorded address is invalid or has a wrong EIP-55 checksum
+        #[arg(long = "fail-on-malformed")]
+        fail_on_malformed: bool,
+        /// Report deployments whose deployed_addresses.json is older than this (e.g. 90d; units s, m, h, d, w) as potentially stale
+        #[arg(long = "max-age", value_name = "AGE", value_parser = parse_age)]
+        max_age: Option<Duration>,
+        /// File listing the chain IDs the project should be deployed to (one per line, or a JSON array); reports chains in it without deployments and deployments not in it
+        #[arg(long = "expected-chains", value_name = "FILE")]
+        expected_chains: Option<PathBuf>,
+        /// Report 4-byte function selectors shared by contracts deployed on the same network (read from their ABIs), which a proxy or diamond can't route to both
+        #[arg(long = "check-selectors")]
+        check_selectors: bool,
+        /// Only show this page of the findings in table output
+        #[arg(long = "page", value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..), conflicts_with_all = ["json", "csv", "sarif", "summary_only"])]
+        page: Option<usize>,
+        /// Number of findings per page with --page
+        #[arg(long = "page-size", default_value_t = 50, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..), requires = "page")]
+        page_size: usize,
+        /// Instead of auditing, list the networks added, removed or given another chain ID relative to this older config
+        #[arg(long = "compare-config", value_name = "OTHER", conflicts_with_all = ["csv", "sarif", "summary_only", "forbid_chains_below", "fail_on_malformed", "max_age", "expected_chains", "check_selectors", "page"])]
+        compare_config: Option<PathBuf>,
+        /// Output file (only valid with --json, --csv, or --sarif)
         #[arg(short = 'o', long = "outfile", requires = "output_format")]
         outfile: Option<PathBuf>,
     },
+    /// Compare the runtime code hash of a network's contracts against expected hashes
+    Verify {
+        /// Network name as defined in the hardhat config
+        #[arg(short = 'n', long = "network")]
+        network: String,
+        /// RPC endpoint of the network
+        #[arg(long = "rpc-url")]
+        rpc_url: String,
+        /// JSON file mapping contract names to the expected keccak256 hash of their runtime bytecode
+        #[arg(long = "expected")]
+        expected: PathBuf,
+        /// Check the nonce and balance of addresses without code to tell a wrongly recorded EOA from a self-destructed contract
+        #[arg(long = "explain-no-code")]
+        explain_no_code: bool,
+        /// Seconds to wait for each RPC call before giving up on it
+        #[arg(long = "rpc-timeout", value_name = "SECS", default_value_t = 30, value_parser = clap::value_parser!(u64).range(1..))]
+        rpc_timeout: u64,
+        /// Times to retry an RPC call that timed out or failed to reach the node, with backoff, before reporting an RPC error
+        #[arg(long = "rpc-retries", value_name = "N", default_value_t = 2)]
+        rpc_retries: u32,
+        /// Also show when each contract's deployment transaction (from the Ignition journal) was mined, next to when deployed_addresses.json was last modified
+        #[arg(long = "verify-onchain-time")]
+        verify_onchain_time: bool,
+        /// Output in JSON format
+        #[arg(short = 'j', long = "json")]
+        json: bool,
+    },
+    /// Show how a contract's address on each network changed between two git refs
+    DiffContract {
+        /// Contract name, either the full Module#Contract key or just the contract name
+        #[arg(short = 'c', long = "contract")]
+        contract: String,
+        /// The older git ref (e.g. v1.0.0)
+        #[arg(long = "from")]
+        from: String,
+        /// The newer git ref
+        #[arg(long = "to", default_value = "HEAD")]
+        to: String,
+        /// Leave out networks where the address didn't change
+        #[arg(long = "only-changes")]
+        only_changes: bool,
+        /// Output in JSON format
+        #[arg(short = 'j', long = "json")]
+        json: bool,
+    },
     /// Display version information
     Version,
     
@@ -82,82 +375,289 @@ enum Commands {
         #[arg(short = 'o', long = "outfile", requires = "output_format")]
         outfile: Option<PathBuf>,
     },
+    /// Detect deployments that are older than their contract sources
+    Stale {
+        /// Output in JSON format
+        #[arg(short = 'j', long = "json", conflicts_with = "csv", group = "output_format")]
+        json: bool,
+        /// Output in CSV format
+        #[arg(short = 'c', long = "csv", conflicts_with = "json", group = "output_format")]
+        csv: bool,
+        /// Output file (only valid with --json or --csv)
+        #[arg(short = 'o', long = "outfile", requires = "output_format")]
+        outfile: Option<PathBuf>,
+    },
+    /// Show the deployments directory as a tree annotated with network names
+    Tree {
+        /// Output in JSON format
+        #[arg(short = 'j', long = "json")]
+        json: bool,
+    },
+    /// Output a Graphviz DOT graph of the proxies, implementations and proxy admins of each network
+    Graph {
+        /// Only graph this network
+        #[arg(short = 'n', long = "network")]
+        network: Option<String>,
+        /// Output file (defaults to printing the graph)
+        #[arg(short = 'o', long = "outfile")]
+        outfile: Option<PathBuf>,
+    },
+    /// Run the count, audit and address checks and exit with a bitmask of the failures
+    Health {
+        /// Output in JSON format
+        #[arg(short = 'j', long = "json")]
+        json: bool,
+    },
+    /// Open a deployment's block explorer page in the browser
+    Open {
+        /// Network name as defined in the hardhat config
+        #[arg(short = 'n', long = "network")]
+        network: String,
+        /// Contract name (defaults to the first contract in the deployment)
+        #[arg(short = 'c', long = "contract")]
+        contract: Option<String>,
+    },
+    /// Show a deployment's address as a QR code, e.g. to scan it into a mobile wallet
+    Qr {
+        /// Network name as defined in the hardhat config
+        #[arg(short = 'n', long = "network")]
+        network: String,
+        /// Contract name (defaults to the first contract in the deployment)
+        #[arg(short = 'c', long = "contract")]
+        contract: Option<String>,
+        /// PNG file to save the QR code to (defaults to printing it in the terminal)
+        #[arg(short = 'o', long = "outfile")]
+        outfile: Option<PathBuf>,
+    },
+    /// Show every address a contract had on a network, from the git history of its deployment
+    History {
+        /// Network name as defined in the hardhat config
+        #[arg(short = 'n', long = "network")]
+        network: String,
+        /// Contract name
+        #[arg(short = 'c', long = "contract")]
+        contract: String,
+        /// Output in JSON format
+        #[arg(short = 'j', long = "json")]
+        json: bool,
+    },
+    /// Print the paths of a deployed contract's Solidity source and artifact JSON
+    Where {
+        /// Network name as defined in the hardhat config
+        #[arg(short = 'n', long = "network")]
+        network: String,
+        /// Contract name (defaults to the first contract in the deployment)
+        #[arg(short = 'c', long = "contract")]
+        contract: Option<String>,
+        /// Output in JSON format
+        #[arg(short = 'j', long = "json")]
+        json: bool,
+    },
+    /// Render a JSON report (e.g. from list --json) through a Tera template
+    Render {
+        /// Tera template file
+        #[arg(long = "template")]
+        template: PathBuf,
+        /// JSON report to render
+        #[arg(long = "input")]
+        input: PathBuf,
+        /// Output file (defaults to printing the result)
+        #[arg(short = 'o', long = "outfile")]
+        outfile: Option<PathBuf>,
+    },
 }
 
-fn validate_hardhat_project(root: &Path) -> Result<(), String> {
-    let config_path = root.join("hardhat.config.ts");
-    if !config_path.exists() {
-        return Err("No hardhat.config.ts found in the specified root directory".to_string());
+fn abbreviate_address(address: &str) -> String {
+    let chars: Vec<char> = address.chars().collect();
+    if chars.len() <= 10 {
+        return address.to_string();
     }
-    Ok(())
+    let head: String = chars[..6].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}…{}", head, tail)
 }
 
-fn count_deployments(root: &Path) -> Result<usize, String> {
-    let deployments_dir = root.join("deployments");
-    if !deployments_dir.exists() {
-        return Ok(0);
+/// Looks up the native token balance of every deployed address, keyed by
+/// (network, address) and formatted in ether. Each network's RPC endpoint comes
+/// from `--balance-rpc` or the config; networks without one and failed lookups
+/// are skipped with a warning.
+fn fetch_balances(ctx: &Context, deployments: &[NetworkDeployment], balance_rpcs: &[String]) -> Result<HashMap<(String, String), String>, String> {
+    // Template literal URLs with ${...} interpolations can't be used as they are
+    let (partial_urls, config_urls): (HashMap<_, _>, HashMap<_, _>) = parse_network_urls(ctx)?.into_iter()
+        .partition(|(_, url)| url.partial);
+    let mut urls: HashMap<String, String> = config_urls.into_iter().map(|(network, url)| (network, url.url)).collect();
+    for entry in balance_rpcs {
+        let (network, url) = entry.split_once('=')
+            .ok_or_else(|| format!("Invalid --balance-rpc {}; expected <network>=<url>", entry))?;
+        urls.insert(network.to_string(), url.to_string());
+    }
+
+    let mut clients: HashMap<&str, rpc::RpcClient> = HashMap::new();
+    let mut lookups = Vec::new();
+    for deployment in deployments {
+        let Some(url) = urls.get(&deployment.network) else {
+            match partial_urls.get(&deployment.network) {
+                Some(partial) => eprintln!("Warning: The RPC URL for {} ({}) interpolates values that can't be resolved, pass --balance-rpc {}=<url> to show its balances", deployment.network, partial.url, deployment.network),
+                None => eprintln!("Warning: No RPC URL for {}, pass --balance-rpc {}=<url> to show its balances", deployment.network, deployment.network),
+            }
+            continue;
+        };
+        if !clients.contains_key(url.as_str()) {
+            clients.insert(url, rpc::RpcClient::new(url)?);
+        }
+        for contract in &deployment.contracts {
+            lookups.push((deployment.network.clone(), contract.address.clone(), url.as_str()));
+        }
+    }
+
+    let mut balances = HashMap::new();
+    for chunk in lookups.chunks(BALANCE_CONCURRENCY) {
+        let results: Vec<Result<u128, String>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = chunk.iter()
+                .map(|(_, address, url)| {
+                    let client = &clients[url];
+                    scope.spawn(move || client.eth_get_balance(address))
+                })
+                .collect();
+            handles.into_iter()
+                .map(|handle| handle.join().unwrap_or_else(|_| Err("Balance lookup panicked".to_string())))
+                .collect()
+        });
+        for ((network, address, _), result) in chunk.iter().zip(results) {
+            match result {
+                Ok(wei) => {
+                    balances.insert((network.clone(), address.clone()), format_ether(wei));
+                }
+                Err(e) => eprintln!("Warning: Balance lookup failed for {} on {}: {}", address, network, e),
+            }
+        }
     }
 
-    match deployments_dir.read_dir() {
-        Ok(entries) => Ok(entries.filter(|e| e.is_ok() && e.as_ref().unwrap().path().is_dir()).count()),
-        Err(e) => Err(format!("Failed to read deployments directory: {}", e)),
+    Ok(balances)
+}
+
+/// Formats a wei amount in ether, without trailing zeros (e.g. 1.5).
+fn format_ether(wei: u128) -> String {
+    const WEI_PER_ETHER: u128 = 1_000_000_000_000_000_000;
+    let whole = wei / WEI_PER_ETHER;
+    let fraction = wei % WEI_PER_ETHER;
+    if fraction == 0 {
+        return whole.to_string();
     }
+    let fraction = format!("{:018}", fraction);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
 }
 
-fn camel_to_title_case(s: &str) -> String {
-    let re = Regex::new(r"([a-z0-9])([A-Z])").unwrap();
-    let spaced = re.replace_all(s, "$1 $2").to_string();
-    spaced.split_whitespace()
-        .map(|word| {
-            let mut chars = word.chars();
-            match chars.next() {
-                None => String::new(),
-                Some(c) => c.to_uppercase().chain(chars).collect(),
+/// Joins the fields of a CSV row with `delimiter`, ending the line. Fields
+/// containing the delimiter, a quote or a line break are quoted, with
+/// embedded quotes doubled (RFC 4180).
+fn csv_row<S: AsRef<str>>(fields: &[S], delimiter: char) -> String {
+    let mut row = fields.iter()
+        .map(|field| {
+            let field = field.as_ref();
+            if field.contains([delimiter, '"', '\r', '\n']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
             }
         })
         .collect::<Vec<_>>()
-        .join(" ")
+        .join(&delimiter.to_string());
+    row.push('\n');
+    row
 }
 
-fn parse_hardhat_config(root: &Path) -> Result<HashMap<String, u64>, String> {
-    let config_path = root.join("hardhat.config.ts");
-    let content = fs::read_to_string(config_path)
-        .map_err(|e| format!("Failed to read hardhat.config.ts: {}", e))?;
-
-    let mut networks = HashMap::new();
-    let network_regex = Regex::new(r#"(\w+):\s*\{[^}]*chainId:\s*(\d+)"#).unwrap();
+/// Formats seconds since the Unix epoch as a UTC date and time, e.g.
+/// 2024-05-01 12:00:00 UTC.
+fn format_timestamp(secs: u64) -> String {
+    // Proleptic Gregorian date of the days since 1970-01-01
+    let days = (secs / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = era * 400 + year_of_era + i64::from(month <= 2);
+    let time = secs % 86400;
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC", year, month, day, time / 3600, time % 3600 / 60, time % 60)
+}
 
-    for cap in network_regex.captures_iter(&content) {
-        let network_name = cap[1].to_string();
-        let chain_id = cap[2].parse::<u64>()
-            .map_err(|_| format!("Invalid chain ID for network {}", network_name))?;
-        networks.insert(network_name, chain_id);
+/// Formats a chain ID for table output, with thousands separators when
+/// `pretty` is set (e.g. 11,155,111).
+fn format_chain_id(chain_id: u64, pretty: bool) -> String {
+    let digits = chain_id.to_string();
+    if !pretty {
+        return digits;
+    }
+    let mut formatted = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
     }
+    formatted
+}
 
-    Ok(networks)
+/// Shortens `text` to `width` characters by replacing its middle with an
+/// ellipsis, keeping both ends of addresses and URLs recognizable.
+fn truncate_middle(text: &str, width: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= width {
+        return text.to_string();
+    }
+    let keep = width.saturating_sub(1);
+    let head: String = chars[..keep.div_ceil(2)].iter().collect();
+    let tail: String = chars[chars.len() - keep / 2..].iter().collect();
+    format!("{}…{}", head, tail)
 }
 
-fn get_deployment_address(deployment_dir: &Path) -> Result<Option<String>, String> {
-    let addresses_path = deployment_dir.join("deployed_addresses.json");
-    if !addresses_path.exists() {
-        return Ok(None);
+/// Narrowest a truncated column gets, enough for `0x1234…abcd`
+const MIN_TRUNCATED_WIDTH: usize = 11;
+
+/// Truncates the cells of the given columns (widest first) so the table
+/// renders within `max_width` terminal columns, if it can.
+fn fit_table_width(table: &mut Table, max_width: usize, truncatable: &[usize]) {
+    let mut widths: Vec<usize> = Vec::new();
+    for row in table.row_iter() {
+        for (i, cell) in row.iter().enumerate() {
+            let width = cell.get_content().chars().count();
+            if i >= widths.len() {
+                widths.push(width);
+            } else {
+                widths[i] = widths[i].max(width);
+            }
+        }
     }
 
-    let content = fs::read_to_string(addresses_path)
-        .map_err(|e| format!("Failed to read deployed_addresses.json: {}", e))?;
-    
-    let data: Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse deployed_addresses.json: {}", e))?;
+    // "│ " and " │" around every cell, sharing the inner borders
+    let total = widths.iter().sum::<usize>() + 3 * widths.len() + 1;
+    let mut excess = total.saturating_sub(max_width);
+    if excess == 0 {
+        return;
+    }
 
-    // Get the first address we find (assuming there's at least one)
-    Ok(data.as_object()
-        .and_then(|obj| obj.values().next())
-        .and_then(|v| v.as_str())
-        .map(String::from))
+    let mut columns: Vec<usize> = truncatable.iter().copied().filter(|&i| i < widths.len()).collect();
+    columns.sort_by_key(|&i| std::cmp::Reverse(widths[i]));
+    for i in columns {
+        let shrink = excess.min(widths[i].saturating_sub(MIN_TRUNCATED_WIDTH));
+        widths[i] -= shrink;
+        excess -= shrink;
+
+        for row in table.row_iter_mut() {
+            if let Some(cell) = row.get_mut_cell(i) {
+                if cell.get_content().chars().count() > widths[i] {
+                    *cell = Cell::new(&truncate_middle(&cell.get_content(), widths[i]));
+                }
+            }
+        }
+    }
 }
 
 fn create_sui_style_format() -> prettytable::format::TableFormat {
-    let format = format::FormatBuilder::new()
+    format::FormatBuilder::new()
         .column_separator('│')
         .borders('│')
         .separator(
@@ -173,543 +673,1782 @@ fn create_sui_style_format() -> prettytable::format::TableFormat {
             format::LineSeparator::new('─', '┼', '├', '┤'),
         )
         .padding(1, 1)
-        .build();
-    format
+        .build()
 }
 
-fn list_deployments(root: &Path, aggregate: bool, json: bool, csv: bool, md: bool, outfile: Option<&Path>) -> Result<(), String> {
-    let networks = parse_hardhat_config(root)?;
-    let deployments_dir = root.join("deployments");
-    
-    let mut found_deployments = Vec::new();
-    let mut missing_deployments = Vec::new();
+/// Latest schema version of `list --json`
+const LIST_JSON_VERSION: u8 = 2;
+
+/// Columns that can be selected for the list table with --columns
+const LIST_COLUMNS: &[&str] = &["network", "module", "contract", "address", "chainid", "explorer", "ens", "tags", "balance", "commit"];
+/// Maximum number of balance lookups in flight at once
+const BALANCE_CONCURRENCY: usize = 8;
+/// Fields `--fields` can select in JSON and CSV output
+const LIST_FIELDS: &[&str] = &["network", "contract", "address", "chainid"];
+
+/// How `list` renders the report [`ListSelection`] picked.
+struct ListRenderOptions<'a> {
+    aggregate: bool,
+    group_threshold: usize,
+    group_sort: &'a str,
+    json: bool,
+    json_version: u8,
+    json_array: bool,
+    include_missing: bool,
+    csv: bool,
+    csv_bom: bool,
+    csv_delimiter: char,
+    md: bool,
+    outfile: Option<&'a Path>,
+    output_mode: &'a str,
+    dedupe: bool,
+    clipboard: bool,
+    no_header: bool,
+    abbrev: bool,
+    max_width: Option<usize>,
+    columns: &'a [String],
+    fields: &'a [String],
+    collapse_identical: bool,
+    show_balances: bool,
+    balance_rpcs: &'a [String],
+    show_module: bool,
+    fail_on_malformed: bool,
+    max_age: Option<Duration>,
+    explain: bool,
+    by_contract: bool,
+    resolve_ens: bool,
+    rpc_url: Option<&'a str>,
+    pretty_numbers: bool,
+    redact_addresses: bool,
+    /// Derived from the selection, see [`ListSelection::shows_contracts`]
+    show_contracts: bool,
+    /// See [`ListSelection::missing_description`]
+    missing_description: &'static str,
+    show_tags: bool,
+    /// Whether --since-commit recorded the commit of each deployment
+    show_commits: bool,
+    normalize_network_names: bool,
+}
 
-    for (network_name, chain_id) in networks {
-        if network_name == "hardhat" {
-            continue;
-        }
+/// Where list output goes: straight to stdout, or with `--clipboard` into a
+/// buffer that is copied to the clipboard once everything is rendered.
+struct Output {
+    buffer: Option<String>,
+}
 
-        let chain_dir = deployments_dir.join(format!("chain-{}", chain_id));
-        
-        match get_deployment_address(&chain_dir) {
-            Ok(Some(address)) => {
-                found_deployments.push((network_name, address));
-            }
-            Ok(None) => {
-                missing_deployments.push(network_name);
-            }
-            Err(e) => eprintln!("Warning: Error reading deployment for {}: {}", network_name, e),
-        }
+impl Output {
+    fn new(clipboard: bool) -> Self {
+        Self { buffer: clipboard.then(String::new) }
     }
 
-    if json {
-        let mut output = serde_json::Map::new();
-        
-        if !found_deployments.is_empty() {
-            if aggregate {
-                let mut grouped = serde_json::Map::new();
-                for (network, address) in found_deployments {
-                    let parts: Vec<&str> = network.split(|c: char| c.is_uppercase()).collect();
-                    let prefix = parts[0].to_string();
-                    let suffix = network[prefix.len()..].to_string();
-                    
-                    let suffix = if suffix.is_empty() {
-                        "Mainnet".to_string()
-                    } else {
-                        suffix
-                    };
-                    
-                    let entry = grouped.entry(prefix).or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
-                    if let Some(obj) = entry.as_object_mut() {
-                        obj.insert(suffix, serde_json::Value::String(address));
-                    }
-                }
-                output.insert("deployments".to_string(), serde_json::Value::Object(grouped));
-            } else {
-                let mut deployments = serde_json::Map::new();
-                for (network, address) in found_deployments {
-                    deployments.insert(network, serde_json::Value::String(address));
-                }
-                output.insert("deployments".to_string(), serde_json::Value::Object(deployments));
-            }
+    fn print(&mut self, text: &str) {
+        match &mut self.buffer {
+            Some(buffer) => buffer.push_str(text),
+            None => print!("{}", text),
         }
+    }
 
-        if !missing_deployments.is_empty() {
-            if aggregate {
-                let mut grouped = serde_json::Map::new();
-                for network in missing_deployments {
-                    let parts: Vec<&str> = network.split(|c: char| c.is_uppercase()).collect();
-                    let prefix = parts[0].to_string();
-                    let suffix = network[prefix.len()..].to_string();
-                    
-                    let suffix = if suffix.is_empty() {
-                        "Mainnet".to_string()
-                    } else {
-                        suffix
-                    };
-                    
-                    let entry = grouped.entry(prefix).or_insert_with(|| serde_json::Value::Array(Vec::new()));
-                    if let Some(arr) = entry.as_array_mut() {
-                        arr.push(serde_json::Value::String(suffix));
-                    }
-                }
-                output.insert("missing".to_string(), serde_json::Value::Object(grouped));
-            } else {
-                output.insert(
-                    "missing".to_string(),
-                    serde_json::Value::Array(
-                        missing_deployments.into_iter()
-                            .map(serde_json::Value::String)
-                            .collect()
-                    )
-                );
+    fn println(&mut self, text: &str) {
+        self.print(text);
+        self.print("\n");
+    }
+
+    fn table(&mut self, table: &Table) {
+        match &mut self.buffer {
+            // Plain text, without the terminal colors printstd adds
+            Some(buffer) => buffer.push_str(&table.to_string()),
+            None => {
+                table.printstd();
             }
         }
+    }
 
-        let output = serde_json::to_string_pretty(&output).map_err(|e| e.to_string())?;
-        if let Some(path) = outfile {
-            if let Some(parent) = path.parent() {
-                fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    /// Copies the buffered output to the clipboard, printing it instead when
+    /// there is no clipboard to copy to.
+    fn finish(self) {
+        let Some(text) = self.buffer else { return };
+        match copy_to_clipboard(&text) {
+            Ok(()) => eprintln!("Copied the output to the clipboard"),
+            Err(e) => {
+                eprintln!("Warning: Failed to copy to the clipboard ({}), printing instead", e);
+                print!("{}", text);
             }
-            fs::write(path, output).map_err(|e| format!("Failed to write to file: {}", e))?;
-        } else {
-            println!("{}", output);
         }
-    } else if csv {
-        let mut csv_content = String::from("Chain,Network,Address\n");
-        if aggregate {
-            let mut grouped: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
-            for (network, address) in found_deployments {
-                let parts: Vec<&str> = network.split(|c: char| c.is_uppercase()).collect();
-                let prefix = parts[0].to_string();
-                let suffix = network[prefix.len()..].to_string();
-                
-                let suffix = if suffix.is_empty() {
-                    "Mainnet".to_string()
-                } else {
-                    suffix
-                };
-                
-                grouped.entry(prefix)
-                    .or_default()
-                    .push((suffix, address));
-            }
+    }
+}
 
-            for (prefix, mut networks) in grouped {
-                networks.sort_by(|a, b| {
-                    if a.0 == "Mainnet" {
-                        std::cmp::Ordering::Less
-                    } else if b.0 == "Mainnet" {
-                        std::cmp::Ordering::Greater
-                    } else {
-                        a.0.cmp(&b.0)
-                    }
-                });
-                
-                for (suffix, address) in networks {
-                    csv_content.push_str(&format!("{},{},{}\n",
-                        camel_to_title_case(&prefix),
-                        camel_to_title_case(&suffix),
-                        address
-                    ));
-                }
-            }
+/// Writes a command's output to `path`, creating its parent directories.
+fn write_output(path: &Path, content: impl AsRef<[u8]>) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    fs::write(path, content).map_err(|e| format!("Failed to write to file: {}", e))
+}
 
-            if !missing_deployments.is_empty() {
-                csv_content.push_str("\nMissing Networks\nChain,Network\n");
-                for network in missing_deployments {
-                    let parts: Vec<&str> = network.split(|c: char| c.is_uppercase()).collect();
-                    let prefix = parts[0].to_string();
-                    let suffix = network[prefix.len()..].to_string();
-                    
-                    let suffix = if suffix.is_empty() {
-                        "Mainnet".to_string()
-                    } else {
-                        suffix
-                    };
-                    
-                    csv_content.push_str(&format!("{},{}\n",
-                        camel_to_title_case(&prefix),
-                        camel_to_title_case(&suffix)
-                    ));
-                }
-            }
-        } else {
-            for (network, address) in found_deployments {
-                csv_content.push_str(&format!("{},{},{}\n", camel_to_title_case(&network), network, address));
-            }
-            
-            if !missing_deployments.is_empty() {
-                csv_content.push_str("\nMissing Networks\nChain,Network\n");
-                for network in missing_deployments {
-                    csv_content.push_str(&format!("{},{}\n", camel_to_title_case(&network), network));
-                }
-            }
-        }
+/// Set in the environment of the background process that keeps serving the
+/// clipboard contents on Linux, see [`copy_to_clipboard`]
+#[cfg(target_os = "linux")]
+const CLIPBOARD_HOLDER_ENV: &str = "EVM_DEPLOYMENT_INFO_CLIPBOARD_HOLDER";
 
-        if let Some(path) = outfile {
-            fs::write(path, csv_content).map_err(|e| format!("Failed to write to file: {}", e))?;
-        } else {
-            print!("{}", csv_content);
-        }
-    } else if md {
-        let mut md_content = String::new();
-        
-        if !found_deployments.is_empty() {
-            if aggregate {
-                let mut grouped: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
-                for (network, address) in found_deployments.clone() {
-                    let parts: Vec<&str> = network.split(|c: char| c.is_uppercase()).collect();
-                    let prefix = parts[0].to_string();
-                    let suffix = network[prefix.len()..].to_string();
-                    
-                    let suffix = if suffix.is_empty() {
-                        "Mainnet".to_string()
-                    } else {
-                        suffix
-                    };
-                    
-                    grouped.entry(prefix)
-                        .or_default()
-                        .push((suffix, address));
-                }
+/// How long to wait for the clipboard holder to take over the clipboard
+#[cfg(target_os = "linux")]
+const CLIPBOARD_HANDOFF_TIMEOUT: Duration = Duration::from_secs(5);
 
-                md_content.push_str(&format!("Found {} Ecosystem(s) for a total of {} deployment(s):\n\n", 
-                    grouped.len(),
-                    found_deployments.len()
-                ));
-
-                for (prefix, mut networks) in grouped {
-                    networks.sort_by(|a, b| {
-                        if a.0 == "Mainnet" {
-                            std::cmp::Ordering::Less
-                        } else if b.0 == "Mainnet" {
-                            std::cmp::Ordering::Greater
-                        } else {
-                            a.0.cmp(&b.0)
-                        }
-                    });
-                    
-                    md_content.push_str(&format!("# {}\n\n", camel_to_title_case(&prefix)));
-                    md_content.push_str("| Network | Address |\n|---------|----------|\n");
-                    
-                    for (suffix, address) in networks {
-                        md_content.push_str(&format!("| {} | `{}` |\n",
-                            camel_to_title_case(&suffix),
-                            address
-                        ));
-                    }
-                    md_content.push_str("\n");
-                }
-            } else {
-                md_content.push_str(&format!("Found {} deployment(s):\n\n", found_deployments.len()));
-                md_content.push_str("| Network | Address |\n|---------|----------|\n");
-                
-                let mut sorted_deployments = found_deployments.clone();
-                sorted_deployments.sort_by(|a, b| a.0.cmp(&b.0));
-                for (network, address) in sorted_deployments {
-                    md_content.push_str(&format!("| {} | `{}` |\n",
-                        camel_to_title_case(&network),
-                        address
-                    ));
-                }
-                md_content.push_str("\n");
-            }
+#[cfg(not(target_os = "linux"))]
+fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(|e| e.to_string())
+}
+
+/// On X11 and Wayland the clipboard contents are served by the process that
+/// set them and vanish when it exits, so they're handed to a background copy
+/// of this binary that serves them until something else is copied. Success is
+/// only reported once the clipboard reads back the output.
+#[cfg(target_os = "linux")]
+fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    use std::io::{Read, Write};
+    use std::os::unix::process::CommandExt;
+    use std::process::Stdio;
+
+    let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
+    let exe = std::env::current_exe().map_err(|e| format!("failed to locate the executable: {}", e))?;
+    let mut holder = Command::new(exe)
+        .env(CLIPBOARD_HOLDER_ENV, "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .current_dir("/")
+        // Its own process group, so Ctrl-C in the terminal doesn't clear the clipboard
+        .process_group(0)
+        .spawn()
+        .map_err(|e| format!("failed to start the clipboard holder: {}", e))?;
+    holder.stdin.take().expect("stdin is piped").write_all(text.as_bytes())
+        .map_err(|e| format!("failed to hand the output to the clipboard holder: {}", e))?;
+    let deadline = std::time::Instant::now() + CLIPBOARD_HANDOFF_TIMEOUT;
+    loop {
+        if let Some(status) = holder.try_wait().map_err(|e| e.to_string())? {
+            let mut error = String::new();
+            holder.stderr.take().expect("stderr is piped").read_to_string(&mut error).ok();
+            return Err(match error.trim() {
+                "" => format!("the clipboard holder exited with {}", status),
+                error => error.to_string(),
+            });
+        }
+        if clipboard.get_text().is_ok_and(|current| current == text) {
+            return Ok(());
         }
+        if std::time::Instant::now() >= deadline {
+            holder.kill().ok();
+            return Err("timed out waiting for the clipboard holder".to_string());
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
 
-        if !missing_deployments.is_empty() {
-            md_content.push_str(&format!("\n### Missing Deployments\n\nFound the following {} chain(s) in hardhat config without corresponding deployment(s):\n\n",
-                missing_deployments.len()));
-            
-            if aggregate {
-                let mut grouped: BTreeMap<String, Vec<String>> = BTreeMap::new();
-                for network in missing_deployments {
-                    let parts: Vec<&str> = network.split(|c: char| c.is_uppercase()).collect();
-                    let prefix = parts[0].to_string();
-                    let suffix = network[prefix.len()..].to_string();
-                    
-                    let suffix = if suffix.is_empty() {
-                        "Mainnet".to_string()
-                    } else {
-                        suffix
-                    };
-                    
-                    grouped.entry(prefix)
-                        .or_default()
-                        .push(suffix);
-                }
+/// Runs in the background process started by [`copy_to_clipboard`]: puts
+/// stdin on the clipboard and serves it until something else is copied.
+#[cfg(target_os = "linux")]
+fn hold_clipboard() -> Result<(), String> {
+    use arboard::SetExtLinux;
+    use std::io::Read;
+
+    let mut text = String::new();
+    std::io::stdin().read_to_string(&mut text).map_err(|e| format!("failed to read the output: {}", e))?;
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set().wait().text(text))
+        .map_err(|e| e.to_string())
+}
 
-                for (prefix, mut networks) in grouped {
-                    networks.sort_by(|a, b| {
-                        if a == "Mainnet" {
-                            std::cmp::Ordering::Less
-                        } else if b == "Mainnet" {
-                            std::cmp::Ordering::Greater
-                        } else {
-                            a.cmp(b)
-                        }
-                    });
-                    
-                    md_content.push_str(&format!("#### {}\n\n", camel_to_title_case(&prefix)));
-                    md_content.push_str("| Network |\n|---------|\n");
-                    for suffix in networks {
-                        md_content.push_str(&format!("| {} |\n", camel_to_title_case(&suffix)));
-                    }
-                    md_content.push_str("\n");
-                }
-            } else {
-                md_content.push_str("| Network |\n|---------|\n");
-                let mut sorted_missing = missing_deployments.clone();
-                sorted_missing.sort();
-                for network in sorted_missing {
-                    md_content.push_str(&format!("| {} |\n", camel_to_title_case(&network)));
-                }
-                md_content.push_str("\n");
-            }
-        }
+fn list_deployments(ctx: &Context, selection: &ListSelection, options: &ListRenderOptions) -> Result<(), String> {
+    let mut report = build_selected_report(ctx, selection)?;
+    for warning in &report.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    for name in &report.unmatched_contracts {
+        eprintln!("Warning: Contract {} not found in any deployment", name);
+    }
 
-        if let Some(path) = outfile {
-            fs::write(path, md_content).map_err(|e| format!("Failed to write to file: {}", e))?;
-        } else {
-            print!("{}", md_content);
-        }
-    } else {
-        if !found_deployments.is_empty() {
-            if aggregate {
-                let mut grouped: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
-                for (network, address) in found_deployments.clone() {
-                    let parts: Vec<&str> = network.split(|c: char| c.is_uppercase()).collect();
-                    let prefix = parts[0].to_string();
-                    let suffix = network[prefix.len()..].to_string();
-                    
-                    let suffix = if suffix.is_empty() {
-                        "Mainnet".to_string()
-                    } else {
-                        suffix
-                    };
-                    
-                    grouped.entry(prefix)
-                        .or_default()
-                        .push((suffix, address));
-                }
+    let malformed = if options.fail_on_malformed { report.malformed_addresses() } else { Vec::new() };
+    if options.redact_addresses {
+        report.redact_addresses();
+    }
 
-                println!("Found {} Ecosystem(s) for a total of {} deployment(s):", 
-                    grouped.len(),
-                    found_deployments.len()
-                );
-
-                let mut table = Table::new();
-                table.set_format(create_sui_style_format());
-                table.add_row(row![bF-> "Network", bF-> "Address"]);
-
-                for (prefix, mut networks) in grouped {
-                    networks.sort_by(|a, b| {
-                        if a.0 == "Mainnet" {
-                            std::cmp::Ordering::Less
-                        } else if b.0 == "Mainnet" {
-                            std::cmp::Ordering::Greater
-                        } else {
-                            a.0.cmp(&b.0)
-                        }
-                    });
-                    
-                    table.add_row(row![bF-> format!("{}:", camel_to_title_case(&prefix)), ""]);
-                    
-                    for (suffix, address) in networks {
-                        table.add_row(row![
-                            format!("  {}", camel_to_title_case(&suffix)),
-                            address
-                        ]);
-                    }
-                }
-                table.printstd();
-            } else {
-                println!("Found {} deployment(s):", found_deployments.len());
-                
-                let mut table = Table::new();
-                table.set_format(create_sui_style_format());
-                table.add_row(row![bF-> "Network", bF-> "Address"]);
-                
-                found_deployments.sort_by(|a, b| a.0.cmp(&b.0));
-                for (network, address) in found_deployments {
-                    table.add_row(row![camel_to_title_case(&network), address]);
-                }
-                table.printstd();
-            }
+    let mut out = Output::new(options.clipboard);
+    ctx.timings.time("rendering", || {
+        if options.by_contract {
+            render_list_by_contract(report, options, &mut out)
+        } else {
+            render_list(ctx, report, options, &mut out)
         }
+    })?;
+    out.finish();
 
-        if !missing_deployments.is_empty() {
-            println!("\nFound the following {} chain(s) in hardhat config without corresponding deployment(s):",
-                missing_deployments.len());
-            
-            if aggregate {
-                let mut grouped: BTreeMap<String, Vec<String>> = BTreeMap::new();
-                for network in missing_deployments {
-                    let parts: Vec<&str> = network.split(|c: char| c.is_uppercase()).collect();
-                    let prefix = parts[0].to_string();
-                    let suffix = network[prefix.len()..].to_string();
-                    
-                    let suffix = if suffix.is_empty() {
-                        "Mainnet".to_string()
-                    } else {
-                        suffix
-                    };
-                    
-                    grouped.entry(prefix)
-                        .or_default()
-                        .push(suffix);
-                }
-
-                let mut table = Table::new();
-                table.set_format(create_sui_style_format());
-                table.add_row(row![bF-> "Network"]);
+    report_malformed_addresses(malformed.iter().map(|(network, contract, address)| (network.as_str(), contract.as_str(), address.as_str())), options.redact_addresses)
+}
 
-                for (prefix, mut networks) in grouped {
-                    networks.sort_by(|a, b| {
-                        if a == "Mainnet" {
-                            std::cmp::Ordering::Less
-                        } else if b == "Mainnet" {
-                            std::cmp::Ordering::Greater
-                        } else {
-                            a.cmp(b)
-                        }
-                    });
-                    
-                    table.add_row(row![bF-> format!("{}:", camel_to_title_case(&prefix))]);
-                    for suffix in networks {
-                        table.add_row(row![format!("  {}", camel_to_title_case(&suffix))]);
-                    }
-                }
-                table.printstd();
-            } else {
-                let mut table = Table::new();
-                table.set_format(create_sui_style_format());
-                table.add_row(row![bF-> "Network"]);
-                
-                missing_deployments.sort();
-                for network in missing_deployments {
-                    table.add_row(row![camel_to_title_case(&network)]);
-                }
-                table.printstd();
-            }
+/// Prints each malformed (location, contract, address) entry, with the
+/// address hidden when `redact` is set, and fails when there are any.
+fn report_malformed_addresses<'a>(entries: impl Iterator<Item = (&'a str, &'a str, &'a str)>, redact: bool) -> Result<(), String> {
+    let mut count = 0;
+    for (location, contract, address) in entries {
+        let address = if redact { REDACTED_ADDRESS } else { address };
+        if contract.is_empty() {
+            eprintln!("Malformed address in {}: {}", location, address);
+        } else {
+            eprintln!("Malformed address in {}: {} {}", location, contract, address);
         }
+        count += 1;
+    }
+    if count > 0 {
+        return Err(format!("Found {} malformed address(es)", count));
     }
-
     Ok(())
 }
 
-fn audit_deployments(root: &Path, json: bool, csv: bool, outfile: Option<&Path>) -> Result<(), String> {
-    let networks = parse_hardhat_config(root)?;
-    let deployments_dir = root.join("deployments");
-    
-    let mut config_without_deployment = Vec::new();
-    let mut deployment_without_config = Vec::new();
+fn render_list(ctx: &Context, report: ListReport, options: &ListRenderOptions, out: &mut Output) -> Result<(), String> {
+    let ListRenderOptions { aggregate, group_sort, json, json_version, json_array, csv, md, output_mode, dedupe, columns, fields, collapse_identical, show_balances, show_tags, show_module, resolve_ens, show_commits, show_contracts, normalize_network_names, .. } = *options;
 
-    // Check for configs without deployments
-    for (network_name, chain_id) in &networks {
-        if network_name == "hardhat" {
-            continue;
+    for column in columns {
+        if !LIST_COLUMNS.contains(&column.as_str()) {
+            return Err(format!("Unknown column '{}'. Valid columns are: {}", column, LIST_COLUMNS.join(", ")));
         }
-        let chain_dir = deployments_dir.join(format!("chain-{}", chain_id));
-        if !chain_dir.exists() || get_deployment_address(&chain_dir)?.is_none() {
-            config_without_deployment.push((network_name.clone(), *chain_id));
-        }
-    }
-
-    // Check for deployments without configs
-    if deployments_dir.exists() {
-        for entry in fs::read_dir(&deployments_dir).map_err(|e| e.to_string())? {
-            let entry = entry.map_err(|e| e.to_string())?;
-            let path = entry.path();
-            if path.is_dir() {
-                if let Some(dir_name) = path.file_name().and_then(|n| n.to_str()) {
-                    if let Some(chain_id) = dir_name.strip_prefix("chain-") {
-                        if let Ok(chain_id) = chain_id.parse::<u64>() {
-                            if !networks.values().any(|&id| id == chain_id) {
-                                deployment_without_config.push(chain_id);
-                            }
-                        }
+        if column == "ens" && !resolve_ens {
+            return Err("The ens column requires --resolve-ens".to_string());
+        }
+        if column == "tags" && !show_tags {
+            return Err("The tags column requires --show-tags".to_string());
+        }
+        if column == "balance" && !show_balances {
+            return Err("The balance column requires --show-balances".to_string());
+        }
+        if column == "module" && !show_module {
+            return Err("The module column requires --show-module".to_string());
+        }
+        if column == "commit" && !show_commits {
+            return Err("The commit column requires --since-commit".to_string());
+        }
+        if collapse_identical && !matches!(column.as_str(), "network" | "contract" | "address") {
+            return Err("--collapse-identical only supports the network, contract and address columns".to_string());
+        }
+    }
+    for field in fields {
+        if !LIST_FIELDS.contains(&field.as_str()) {
+            return Err(format!("Unknown field '{}'. Valid fields are: {}", field, LIST_FIELDS.join(", ")));
+        }
+    }
+    if !fields.is_empty() && json && json_version < 2 {
+        return Err("--fields requires version 2 JSON output".to_string());
+    }
+    if !fields.is_empty() && !json && !json_array && !csv {
+        return Err("--fields only applies to --json, --json-array and --csv output".to_string());
+    }
+    // clap declares these conflicts too, but doesn't check them when the
+    // global --redact-addresses comes before the subcommand
+    if options.redact_addresses {
+        let conflicting = [("--show-balances", show_balances), ("--resolve-ens", resolve_ens), ("--collapse-identical", collapse_identical)]
+            .into_iter()
+            .find_map(|(flag, set)| set.then_some(flag));
+        if let Some(flag) = conflicting {
+            return Err(format!("{} can't be used with --redact-addresses", flag));
+        }
+    }
+    // clap makes --dedupe require --output-mode, but can't check its value
+    if dedupe && output_mode != "append-json-array" {
+        return Err("--dedupe requires --output-mode append-json-array".to_string());
+    }
+
+    let mut columns: Vec<String> = if !columns.is_empty() {
+        columns.to_vec()
+    } else if show_contracts {
+        vec!["network".to_string(), "contract".to_string(), "address".to_string()]
+    } else {
+        vec!["network".to_string(), "address".to_string()]
+    };
+    if resolve_ens && !columns.iter().any(|column| column == "ens") {
+        columns.push("ens".to_string());
+    }
+    if show_tags && !columns.iter().any(|column| column == "tags") {
+        columns.push("tags".to_string());
+    }
+    if show_balances && !columns.iter().any(|column| column == "balance") {
+        columns.push("balance".to_string());
+    }
+    if show_commits && !columns.iter().any(|column| column == "commit") {
+        columns.push("commit".to_string());
+    }
+    if show_module && !columns.iter().any(|column| column == "module") {
+        let position = columns.iter().position(|column| column == "contract")
+            .or_else(|| columns.iter().position(|column| column == "network").map(|i| i + 1))
+            .unwrap_or(0);
+        columns.insert(position, "module".to_string());
+    }
+
+    let labels = NetworkLabels {
+        normalize_network_names,
+        default_network: report.default_network.clone(),
+        old_deployments: match options.max_age {
+            Some(max_age) => report.old_deployments(ctx, max_age)?,
+            None => Vec::new(),
+        },
+        deployment_times: if aggregate && group_sort == "first-deployed" {
+            deployment_times(ctx)?
+        } else {
+            BTreeMap::new()
+        },
+    };
+
+    // Table and Markdown output mark and summarize them, other formats warn
+    if json || json_array || csv {
+        for (network, age_days) in &labels.old_deployments {
+            eprintln!("Warning: The deployment on {} is {} day(s) old and may be stale", network, age_days);
+        }
+    }
+
+    if json && json_version >= 2 {
+        render_list_json_v2(report, &labels, options, out)
+    } else if json_array {
+        render_list_json_array(report, options, out)
+    } else if csv && !fields.is_empty() {
+        render_list_csv_fields(report, options, out)
+    } else if json {
+        render_list_json(report, &labels, options, out)
+    } else if csv {
+        render_list_csv(report, &labels, options, out)
+    } else if md {
+        render_list_md(report, &labels, options, out)
+    } else {
+        render_list_table(ctx, report, &labels, &columns, options, out)
+    }
+}
+
+/// How list output names and marks networks, shared by its formats.
+struct NetworkLabels {
+    /// Whether network names are slugs, shown as they are instead of in
+    /// title case
+    normalize_network_names: bool,
+    default_network: Option<String>,
+    /// Deployments older than --max-age, as (network, age in days)
+    old_deployments: Vec<(String, u64)>,
+    /// When each chain was deployed to, for --group-sort first-deployed
+    deployment_times: BTreeMap<u64, SystemTime>,
+}
+
+impl NetworkLabels {
+    /// Slugs are shown as they are, camelCase names as title case
+    fn display_network(&self, network: &str) -> String {
+        if self.normalize_network_names {
+            network.to_string()
+        } else {
+            camel_to_title_case(network)
+        }
+    }
+
+    fn deployed_at(&self, chain_id: u64) -> Option<SystemTime> {
+        self.deployment_times.get(&chain_id).copied()
+    }
+
+    /// Marks the default network's row in table and Markdown output, and the
+    /// rows of deployments older than --max-age
+    fn mark_network(&self, label: String, network: &str) -> String {
+        let label = if self.default_network.as_deref() == Some(network) {
+            format!("{} *", label)
+        } else {
+            label
+        };
+        if self.old_deployments.iter().any(|(old, _)| old == network) {
+            format!("{} ⚠", label)
+        } else {
+            label
+        }
+    }
+
+    /// Whether the default network is among `deployments`, so its mark needs
+    /// a legend.
+    fn shows_default(&self, deployments: &[NetworkDeployment]) -> bool {
+        deployments.iter().any(|deployment| self.default_network.as_deref() == Some(deployment.network.as_str()))
+    }
+
+    fn old_summary(&self, bullet: &str) -> String {
+        let mut summary = format!("⚠ {} deployment(s) older than --max-age, potentially stale:\n", self.old_deployments.len());
+        for (network, age_days) in &self.old_deployments {
+            summary.push_str(&format!("{} {}: {} day(s) old\n", bullet, self.display_network(network), age_days));
+        }
+        summary
+    }
+}
+
+fn render_list_json_v2(report: ListReport, labels: &NetworkLabels, options: &ListRenderOptions, out: &mut Output) -> Result<(), String> {
+    let ListRenderOptions { aggregate, group_threshold, group_sort, json_version, outfile, fields, .. } = *options;
+    let ListReport { deployments: found_deployments, missing, unmatched_contracts, default_network, .. } = report;
+
+    // Version 2 serializes the report itself, so every deployment carries
+    // its chain ID and contract names. With --aggregate, ecosystems of at
+    // least --group-threshold networks move into `groups`, like the tables
+    let (deployments, groups) = if aggregate {
+        let grouped = group_by_ecosystem(found_deployments, |deployment| &deployment.network, group_sort, |deployment| labels.deployed_at(deployment.chain_id));
+        let (flat, grouped): (Vec<_>, Vec<_>) = grouped.into_iter()
+            .partition(|(_, networks)| networks.len() < group_threshold);
+        let groups: Vec<Value> = grouped.into_iter()
+            .map(|(prefix, mut networks)| {
+                networks.sort_by(|a, b| compare_suffixes(&a.0, &b.0));
+                let networks: Vec<NetworkDeployment> = networks.into_iter().map(|(_, deployment)| deployment).collect();
+                json!({ "prefix": prefix, "deployments": networks })
+            })
+            .collect();
+        (flat.into_iter().flat_map(|(_, networks)| networks).map(|(_, deployment)| deployment).collect(), Some(groups))
+    } else {
+        (found_deployments, None)
+    };
+    let mut output = json!({
+        "version": json_version,
+        "deployments": deployments,
+        "missing": missing,
+        "unmatched_contracts": unmatched_contracts,
+        "default_network": default_network,
+    });
+    if let Some(groups) = groups {
+        output["groups"] = json!(groups);
+    }
+    select_report_fields(&mut output, fields);
+
+    let output = to_json(&output)?;
+    if let Some(path) = outfile {
+        write_output(path, output)?;
+    } else {
+        out.println(&output);
+    }
+    Ok(())
+}
+
+/// One JSON record per contract, for tools that want a flat array.
+fn render_list_json_array(report: ListReport, options: &ListRenderOptions, out: &mut Output) -> Result<(), String> {
+    let ListRenderOptions { include_missing, outfile, output_mode, dedupe, fields, .. } = *options;
+    let records = report.json_records(include_missing, fields);
+
+    if let Some(path) = outfile.filter(|_| output_mode == "append-json-array") {
+        let appended = json::append_to_array(path, records, dedupe, JSON_INDENT.load(Ordering::Relaxed))?;
+        eprintln!("Appended {} record(s) to {}", appended, path.display());
+        return Ok(());
+    }
+    let output = to_json(&records)?;
+    if let Some(path) = outfile {
+        write_output(path, output)?;
+    } else {
+        out.println(&output);
+    }
+    Ok(())
+}
+
+fn render_list_csv_fields(report: ListReport, options: &ListRenderOptions, out: &mut Output) -> Result<(), String> {
+    let ListRenderOptions { csv_bom, csv_delimiter, outfile, no_header, fields, .. } = *options;
+    let ListReport { deployments: found_deployments, missing, .. } = report;
+    // Selected fields are written as plain columns in the order given,
+    // with one row per contract and one per missing network
+    let mut csv_content = if csv_bom { String::from('\u{feff}') } else { String::new() };
+    if !no_header {
+        let header: Vec<&str> = fields.iter()
+            .map(|field| match field.as_str() {
+                "network" => "Network",
+                "contract" => "Contract",
+                "address" => "Address",
+                _ => "Chain ID",
+            })
+            .collect();
+        csv_content.push_str(&csv_row(&header, csv_delimiter));
+    }
+    for deployment in &found_deployments {
+        for contract in &deployment.contracts {
+            let row: Vec<String> = fields.iter()
+                .map(|field| match field.as_str() {
+                    "network" => deployment.network.clone(),
+                    "contract" => contract.display_name(),
+                    "address" => contract.address.clone(),
+                    _ => deployment.chain_id.to_string(),
+                })
+                .collect();
+            csv_content.push_str(&csv_row(&row, csv_delimiter));
+        }
+    }
+    for missing in &missing {
+        let row: Vec<String> = fields.iter()
+            .map(|field| match field.as_str() {
+                "network" => missing.network.clone(),
+                "chainid" => missing.chain_id.to_string(),
+                _ => String::new(),
+            })
+            .collect();
+        csv_content.push_str(&csv_row(&row, csv_delimiter));
+    }
+
+    if let Some(path) = outfile {
+        write_output(path, csv_content)?;
+    } else {
+        out.print(&csv_content);
+    }
+    Ok(())
+}
+
+/// Version 1 JSON, mapping networks to addresses.
+fn render_list_json(report: ListReport, labels: &NetworkLabels, options: &ListRenderOptions, out: &mut Output) -> Result<(), String> {
+    let ListRenderOptions { aggregate, group_sort, json_version, outfile, .. } = *options;
+    let show_contracts = options.show_contracts;
+    let found_deployments = report.deployments;
+    let missing_deployments: Vec<String> = report.missing.into_iter()
+        .map(|missing| missing.network)
+        .collect();
+
+    let mut output = serde_json::Map::new();
+    output.insert("version".to_string(), json!(json_version));
+
+    let deployment_value = |contracts: Vec<DeployedContract>| {
+        if show_contracts {
+            serde_json::Value::Object(contracts.into_iter()
+                .map(|contract| (contract.display_name(), serde_json::Value::String(contract.address)))
+                .collect())
+        } else {
+            serde_json::Value::String(contracts.into_iter().next().map(|contract| contract.address).unwrap_or_default())
+        }
+    };
+    
+    if !found_deployments.is_empty() {
+        if aggregate {
+            let grouped = group_by_ecosystem(found_deployments, |deployment| &deployment.network, group_sort, |deployment| labels.deployed_at(deployment.chain_id)).into_iter()
+                .map(|(prefix, networks)| (prefix, serde_json::Value::Object(networks.into_iter()
+                    .map(|(suffix, deployment)| (suffix, deployment_value(deployment.contracts)))
+                    .collect())))
+                .collect();
+            output.insert("deployments".to_string(), serde_json::Value::Object(grouped));
+        } else {
+            let mut deployments = serde_json::Map::new();
+            for deployment in found_deployments {
+                deployments.insert(deployment.network, deployment_value(deployment.contracts));
+            }
+            output.insert("deployments".to_string(), serde_json::Value::Object(deployments));
+        }
+    }
+
+    if !missing_deployments.is_empty() {
+        if aggregate {
+            let grouped = group_by_ecosystem(missing_deployments, |network| network, group_sort, |_| None).into_iter()
+                .map(|(prefix, networks)| (prefix, serde_json::Value::Array(networks.into_iter()
+                    .map(|(suffix, _)| serde_json::Value::String(suffix))
+                    .collect())))
+                .collect();
+            output.insert("missing".to_string(), serde_json::Value::Object(grouped));
+        } else {
+            output.insert(
+                "missing".to_string(),
+                serde_json::Value::Array(
+                    missing_deployments.into_iter()
+                        .map(serde_json::Value::String)
+                        .collect()
+                )
+            );
+        }
+    }
+
+    let output = to_json(&output)?;
+    if let Some(path) = outfile {
+        write_output(path, output)?;
+    } else {
+        out.println(&output);
+    }
+
+    Ok(())
+}
+
+fn render_list_csv(report: ListReport, labels: &NetworkLabels, options: &ListRenderOptions, out: &mut Output) -> Result<(), String> {
+    let ListRenderOptions { aggregate, group_sort, csv_bom, csv_delimiter, outfile, no_header, .. } = *options;
+    let show_contracts = options.show_contracts;
+    let found_deployments = report.deployments;
+    let missing_deployments: Vec<String> = report.missing.into_iter()
+        .map(|missing| missing.network)
+        .collect();
+
+    let mut csv_content = if csv_bom { String::from('\u{feff}') } else { String::new() };
+    if !no_header {
+        let header: &[&str] = if show_contracts { &["Chain", "Network", "Contract", "Address"] } else { &["Chain", "Network", "Address"] };
+        csv_content.push_str(&csv_row(header, csv_delimiter));
+    }
+
+    let push_rows = |csv_content: &mut String, chain: &str, network: &str, contracts: &[DeployedContract]| {
+        for contract in contracts {
+            if show_contracts {
+                csv_content.push_str(&csv_row(&[chain, network, contract.display_name().as_str(), contract.address.as_str()], csv_delimiter));
+            } else {
+                csv_content.push_str(&csv_row(&[chain, network, contract.address.as_str()], csv_delimiter));
+            }
+        }
+    };
+
+    if aggregate {
+        for (prefix, mut networks) in group_by_ecosystem(found_deployments, |deployment| &deployment.network, group_sort, |deployment| labels.deployed_at(deployment.chain_id)) {
+            networks.sort_by(|a, b| compare_suffixes(&a.0, &b.0));
+            
+            for (suffix, deployment) in networks {
+                push_rows(&mut csv_content, &camel_to_title_case(&prefix), &camel_to_title_case(&suffix), &deployment.contracts);
+            }
+        }
+
+        if !missing_deployments.is_empty() {
+            csv_content.push_str("\nMissing Networks\n");
+            csv_content.push_str(&csv_row(&["Chain", "Network"], csv_delimiter));
+            for network in missing_deployments {
+                let (prefix, suffix) = split_network_name(&network);
+                csv_content.push_str(&csv_row(&[camel_to_title_case(&prefix), camel_to_title_case(&suffix)], csv_delimiter));
+            }
+        }
+    } else {
+        for deployment in &found_deployments {
+            push_rows(&mut csv_content, &labels.display_network(&deployment.network), &deployment.network, &deployment.contracts);
+        }
+        
+        if !missing_deployments.is_empty() {
+            csv_content.push_str("\nMissing Networks\n");
+            csv_content.push_str(&csv_row(&["Chain", "Network"], csv_delimiter));
+            for network in missing_deployments {
+                csv_content.push_str(&csv_row(&[labels.display_network(&network), network.clone()], csv_delimiter));
+            }
+        }
+    }
+
+    if let Some(path) = outfile {
+        write_output(path, csv_content)?;
+    } else {
+        out.print(&csv_content);
+    }
+
+    Ok(())
+}
+
+fn render_list_md(report: ListReport, labels: &NetworkLabels, options: &ListRenderOptions, out: &mut Output) -> Result<(), String> {
+    let ListRenderOptions { aggregate, group_threshold, group_sort, outfile, .. } = *options;
+    let show_contracts = options.show_contracts;
+    let missing_description = options.missing_description;
+    let shows_default = labels.shows_default(&report.deployments);
+    let found_deployments = report.deployments;
+    let missing_deployments: Vec<String> = report.missing.into_iter()
+        .map(|missing| missing.network)
+        .collect();
+
+    let mut md_content = String::new();
+    let md_header = if show_contracts {
+        "| Network | Contract | Address |\n|---------|----------|----------|\n"
+    } else {
+        "| Network | Address |\n|---------|----------|\n"
+    };
+
+    let push_rows = |md_content: &mut String, network: &str, contracts: &[DeployedContract]| {
+        for contract in contracts {
+            if show_contracts {
+                md_content.push_str(&format!("| {} | {} | `{}` |\n", network, contract.display_name(), contract.address));
+            } else {
+                md_content.push_str(&format!("| {} | `{}` |\n", network, contract.address));
+            }
+        }
+    };
+    
+    if !found_deployments.is_empty() {
+        if aggregate {
+            let grouped = group_by_ecosystem(&found_deployments, |deployment| &deployment.network, group_sort, |deployment| labels.deployed_at(deployment.chain_id));
+
+            md_content.push_str(&format!("Found {} Ecosystem(s) for a total of {} deployment(s):\n\n", 
+                grouped.len(),
+                found_deployments.len()
+            ));
+
+            // Ecosystems below the threshold share a flat table ahead of the groups
+            let (flat, grouped): (Vec<_>, Vec<_>) = grouped.into_iter()
+                .partition(|(_, networks)| networks.len() < group_threshold);
+            if !flat.is_empty() {
+                md_content.push_str(md_header);
+                for (_, deployment) in flat.into_iter().flat_map(|(_, networks)| networks) {
+                    push_rows(&mut md_content, &labels.mark_network(labels.display_network(&deployment.network), &deployment.network), &deployment.contracts);
+                }
+                md_content.push('\n');
+            }
+
+            for (prefix, mut networks) in grouped {
+                networks.sort_by(|a, b| compare_suffixes(&a.0, &b.0));
+                
+                md_content.push_str(&format!("# {}\n\n", camel_to_title_case(&prefix)));
+                md_content.push_str(md_header);
+                
+                for (suffix, deployment) in networks {
+                    push_rows(&mut md_content, &labels.mark_network(camel_to_title_case(&suffix), &deployment.network), &deployment.contracts);
+                }
+                md_content.push('\n');
+            }
+        } else {
+            md_content.push_str(&format!("Found {} deployment(s):\n\n", found_deployments.len()));
+            md_content.push_str(md_header);
+            
+            for deployment in &found_deployments {
+                push_rows(&mut md_content, &labels.mark_network(labels.display_network(&deployment.network), &deployment.network), &deployment.contracts);
+            }
+            md_content.push('\n');
+        }
+
+        if shows_default {
+            md_content.push_str("\\* Default network\n\n");
+        }
+        if !labels.old_deployments.is_empty() {
+            md_content.push_str(&labels.old_summary("-"));
+            md_content.push('\n');
+        }
+    }
+
+    if !missing_deployments.is_empty() {
+        md_content.push_str(&format!("\n### Missing Deployments\n\nFound the following {} chain(s) {}:\n\n",
+            missing_deployments.len(), missing_description));
+        
+        if aggregate {
+            let (flat, grouped): (Vec<_>, Vec<_>) = group_by_ecosystem(missing_deployments, |network| network, group_sort, |_| None).into_iter()
+                .partition(|(_, networks)| networks.len() < group_threshold);
+            if !flat.is_empty() {
+                md_content.push_str("| Network |\n|---------|\n");
+                for (_, network) in flat.into_iter().flat_map(|(_, networks)| networks) {
+                    md_content.push_str(&format!("| {} |\n", labels.display_network(&network)));
+                }
+                md_content.push('\n');
+            }
+
+            for (prefix, mut networks) in grouped {
+                networks.sort_by(|a, b| compare_suffixes(&a.0, &b.0));
+                
+                md_content.push_str(&format!("#### {}\n\n", camel_to_title_case(&prefix)));
+                md_content.push_str("| Network |\n|---------|\n");
+                for (suffix, _) in networks {
+                    md_content.push_str(&format!("| {} |\n", camel_to_title_case(&suffix)));
+                }
+                md_content.push('\n');
+            }
+        } else {
+            md_content.push_str("| Network |\n|---------|\n");
+            for network in &missing_deployments {
+                md_content.push_str(&format!("| {} |\n", labels.display_network(network)));
+            }
+            md_content.push('\n');
+        }
+    }
+
+    if let Some(path) = outfile {
+        write_output(path, md_content)?;
+    } else {
+        out.print(&md_content);
+    }
+
+    Ok(())
+}
+
+fn render_list_table(ctx: &Context, report: ListReport, labels: &NetworkLabels, columns: &[String], options: &ListRenderOptions, out: &mut Output) -> Result<(), String> {
+    let ListRenderOptions { aggregate, group_threshold, group_sort, no_header, abbrev, max_width, collapse_identical, show_balances, balance_rpcs, show_module, resolve_ens, rpc_url, pretty_numbers, explain, redact_addresses, .. } = *options;
+    let missing_description = options.missing_description;
+    let shows_default = labels.shows_default(&report.deployments);
+
+    let rpc = match rpc_url {
+        Some(url) if resolve_ens => Some(rpc::RpcClient::new(url)?),
+        _ => None,
+    };
+    // ENS names looked up so far, so repeated addresses only cost one lookup
+    let mut ens_cache: HashMap<String, Option<String>> = HashMap::new();
+
+    let missing_reasons = if explain {
+        report.missing_reasons(ctx)?
+    } else {
+        HashMap::new()
+    };
+    let found_deployments = report.deployments;
+    let missing_deployments: Vec<String> = report.missing.into_iter()
+        .map(|missing| missing.network)
+        .collect();
+
+    let balances = if show_balances {
+        fetch_balances(ctx, &found_deployments, balance_rpcs)?
+    } else {
+        HashMap::new()
+    };
+
+    let mut add_rows = |table: &mut Table, label: String, deployment: NetworkDeployment| {
+        let label = labels.mark_network(label, &deployment.network);
+        let chain_id = deployment.chain_id;
+        let commit = deployment.commit.unwrap_or_else(|| "-".to_string());
+        let names: Vec<String> = deployment.contracts.iter()
+            .map(|deployed| if show_module { deployed.display_name_without_module(&deployment.contracts) } else { deployed.display_name() })
+            .collect();
+        for (deployed, contract) in deployment.contracts.into_iter().zip(names) {
+            let module = split_contract_key(&deployed.name).0.unwrap_or("-").to_string();
+            let tags = if deployed.tags.is_empty() { "-".to_string() } else { deployed.tags.join(", ") };
+            let address = deployed.address;
+            // Reverse records live on Ethereum mainnet, so only mainnet
+            // deployments are looked up
+            let ens_name = match &rpc {
+                Some(rpc) if chain_id == 1 => ens_cache.entry(address.clone())
+                    .or_insert_with(|| ens::reverse_lookup(rpc, &address).unwrap_or_else(|e| {
+                        eprintln!("Warning: ENS lookup failed for {}: {}", address, e);
+                        None
+                    }))
+                    .clone(),
+                _ => None,
+            };
+            let cells = columns.iter().map(|column| match column.as_str() {
+                "network" => cell!(label),
+                "module" => cell!(module),
+                "contract" => cell!(contract),
+                "address" if abbrev => cell!(abbreviate_address(&address)),
+                "address" => cell!(address),
+                "chainid" => cell!(format_chain_id(chain_id, pretty_numbers)),
+                // A link to the redacted address would lead nowhere, and
+                // one to the real address would reveal it
+                "explorer" if redact_addresses => cell!("-"),
+                "explorer" => cell!(explorer::address_url(chain_id, &address).unwrap_or_else(|| "-".to_string())),
+                "ens" => cell!(ens_name.clone().unwrap_or_default()),
+                "tags" => cell!(tags),
+                "commit" => cell!(commit),
+                "balance" => cell!(r-> balances.get(&(deployment.network.clone(), address.clone())).map_or("-", String::as_str)),
+                _ => cell!(""),
+            }).collect();
+            table.add_row(Row::new(cells));
+        }
+    };
+
+    if !found_deployments.is_empty() {
+        let mut table = Table::new();
+        table.set_format(create_sui_style_format());
+        if !no_header {
+            table.add_row(Row::new(columns.iter().map(|column| {
+                let header = match column.as_str() {
+                    "network" => "Network",
+                    "module" => "Module",
+                    "contract" => "Contract",
+                    "address" => "Address",
+                    "chainid" => "Chain ID",
+                    "explorer" => "Explorer",
+                    "ens" => "ENS",
+                    "tags" => "Tags",
+                    "balance" => "Balance",
+                    "commit" => "Commit",
+                    _ => "",
+                };
+                cell!(bF-> header)
+            }).collect()));
+        }
+
+        if aggregate {
+            let deployment_count = found_deployments.len();
+            let grouped = group_by_ecosystem(found_deployments, |deployment| &deployment.network, group_sort, |deployment| labels.deployed_at(deployment.chain_id));
+
+            out.println(&format!("Found {} Ecosystem(s) for a total of {} deployment(s):", 
+                grouped.len(),
+                deployment_count
+            ));
+
+            for (prefix, mut networks) in grouped {
+                if networks.len() < group_threshold {
+                    for (_, deployment) in networks {
+                        add_rows(&mut table, labels.display_network(&deployment.network), deployment);
                     }
+                    continue;
                 }
+                networks.sort_by(|a, b| compare_suffixes(&a.0, &b.0));
+                
+                let mut header = vec![cell!(bF-> format!("{}:", camel_to_title_case(&prefix)))];
+                header.resize(columns.len(), cell!(""));
+                table.add_row(Row::new(header));
+                
+                for (suffix, deployment) in networks {
+                    add_rows(&mut table, format!("  {}", camel_to_title_case(&suffix)), deployment);
+                }
+            }
+        } else if collapse_identical {
+            out.println(&format!("Found {} deployment(s):", found_deployments.len()));
+
+            for (contract, address, networks) in collapse_identical_contracts(&found_deployments) {
+                let networks: Vec<String> = networks.iter()
+                    .map(|network| labels.mark_network(labels.display_network(network), network))
+                    .collect();
+                let cells = columns.iter().map(|column| match column.as_str() {
+                    "network" => cell!(networks.join(", ")),
+                    "contract" => cell!(contract),
+                    "address" if abbrev => cell!(abbreviate_address(&address)),
+                    "address" => cell!(address),
+                    _ => cell!(""),
+                }).collect();
+                table.add_row(Row::new(cells));
             }
+        } else {
+            out.println(&format!("Found {} deployment(s):", found_deployments.len()));
+            
+            for deployment in found_deployments {
+                add_rows(&mut table, labels.display_network(&deployment.network), deployment);
+            }
+        }
+        if let Some(max_width) = max_width {
+            let truncatable: Vec<usize> = columns.iter()
+                .enumerate()
+                .filter(|(_, column)| matches!(column.as_str(), "address" | "explorer"))
+                .map(|(i, _)| i)
+                .collect();
+            fit_table_width(&mut table, max_width, &truncatable);
+        }
+        out.table(&table);
+        if shows_default {
+            out.println("* Default network");
+        }
+        if !labels.old_deployments.is_empty() {
+            out.print(&labels.old_summary("  -"));
         }
     }
 
+    if !missing_deployments.is_empty() {
+        out.println(&format!("\nFound the following {} chain(s) {}:",
+            missing_deployments.len(), missing_description));
+        
+        let mut table = Table::new();
+        table.set_format(create_sui_style_format());
+        if !no_header {
+            if explain {
+                table.add_row(row![bF-> "Network", bF-> "Reason"]);
+            } else {
+                table.add_row(row![bF-> "Network"]);
+            }
+        }
+
+        if aggregate {
+            for (prefix, mut networks) in group_by_ecosystem(missing_deployments, |network| network, group_sort, |_| None) {
+                if networks.len() < group_threshold {
+                    for (_, network) in networks {
+                        table.add_row(row![labels.display_network(&network)]);
+                    }
+                    continue;
+                }
+                networks.sort_by(|a, b| compare_suffixes(&a.0, &b.0));
+                
+                table.add_row(row![bF-> format!("{}:", camel_to_title_case(&prefix))]);
+                for (suffix, _) in networks {
+                    table.add_row(row![format!("  {}", camel_to_title_case(&suffix))]);
+                }
+            }
+        } else if explain {
+            for network in missing_deployments {
+                table.add_row(row![labels.display_network(&network), missing_reasons[&network]]);
+            }
+        } else {
+            for network in missing_deployments {
+                table.add_row(row![labels.display_network(&network)]);
+            }
+        }
+        out.table(&table);
+    }
+
+    Ok(())
+}
+
+fn render_list_by_contract(report: ListReport, options: &ListRenderOptions, out: &mut Output) -> Result<(), String> {
+    let ListRenderOptions { json, csv, csv_bom, csv_delimiter, md, outfile, no_header, abbrev, max_width, .. } = *options;
+
+    let by_contract = report.contracts_by_name();
+
     if json {
-        let mut output = serde_json::Map::new();
-        output.insert(
-            "config_without_deployment".to_string(),
-            serde_json::json!(config_without_deployment
-                .iter()
-                .map(|(name, id)| {
-                    serde_json::json!({
-                        "network": name,
-                        "chain_id": id
-                    })
-                })
-                .collect::<Vec<_>>())
-        );
-        output.insert(
-            "deployment_without_config".to_string(),
-            serde_json::json!(deployment_without_config)
-        );
-
-        let output = serde_json::to_string_pretty(&output).map_err(|e| e.to_string())?;
+        let output = to_json(&by_contract)?;
+        if let Some(path) = outfile {
+            write_output(path, output)?;
+        } else {
+            out.println(&output);
+        }
+    } else if csv {
+        let mut csv_content = if csv_bom { String::from('\u{feff}') } else { String::new() };
+        if !no_header {
+            csv_content.push_str(&csv_row(&["Contract", "Network", "Address"], csv_delimiter));
+        }
+        for (contract, networks) in &by_contract {
+            for (network, address) in networks {
+                csv_content.push_str(&csv_row(&[contract, network, address], csv_delimiter));
+            }
+        }
+
+        if let Some(path) = outfile {
+            write_output(path, csv_content)?;
+        } else {
+            out.print(&csv_content);
+        }
+    } else if md {
+        let mut md_content = format!("Found {} contract(s):\n\n", by_contract.len());
+        for (contract, networks) in &by_contract {
+            md_content.push_str(&format!("# {}\n\n", contract));
+            md_content.push_str("| Network | Address |\n|---------|----------|\n");
+            for (network, address) in networks {
+                md_content.push_str(&format!("| {} | `{}` |\n", camel_to_title_case(network), address));
+            }
+            md_content.push('\n');
+        }
+
         if let Some(path) = outfile {
-            if let Some(parent) = path.parent() {
-                fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+            write_output(path, md_content)?;
+        } else {
+            out.print(&md_content);
+        }
+    } else {
+        out.println(&format!("Found {} contract(s):", by_contract.len()));
+
+        let mut table = Table::new();
+        table.set_format(create_sui_style_format());
+        if !no_header {
+            table.add_row(row![bF-> "Network", bF-> "Address"]);
+        }
+
+        for (contract, networks) in by_contract {
+            table.add_row(row![bF-> format!("{}:", contract), ""]);
+            for (network, address) in networks {
+                let address = if abbrev { abbreviate_address(&address) } else { address };
+                table.add_row(row![format!("  {}", camel_to_title_case(&network)), address]);
+            }
+        }
+        if let Some(max_width) = max_width {
+            fit_table_width(&mut table, max_width, &[1]);
+        }
+        out.table(&table);
+    }
+
+    Ok(())
+}
+
+fn list_networks(ctx: &Context, json: bool, pretty_numbers: bool) -> Result<(), String> {
+    let mut networks: Vec<(String, u64)> = parse_hardhat_config(ctx)?.into_iter().collect();
+    networks.sort();
+
+    if json {
+        let output: Vec<Value> = networks.iter()
+            .map(|(network, chain_id)| json!({ "network": network, "chainId": chain_id }))
+            .collect();
+        println!("{}", to_json(&output)?);
+    } else {
+        println!("Found {} network(s) in hardhat config:", networks.len());
+        let mut table = Table::new();
+        table.set_format(create_sui_style_format());
+        table.add_row(row![bF-> "Network", bF-> "Chain ID"]);
+        for (network, chain_id) in networks {
+            table.add_row(row![network, format_chain_id(chain_id, pretty_numbers)]);
+        }
+        table.printstd();
+    }
+
+    Ok(())
+}
+
+struct VerifyOptions<'a> {
+    network: &'a str,
+    rpc_url: &'a str,
+    expected: &'a Path,
+    explain_no_code: bool,
+    rpc_timeout: Duration,
+    rpc_retries: u32,
+    verify_onchain_time: bool,
+    json: bool,
+    redact_addresses: bool,
+}
+
+fn verify_deployments(ctx: &Context, options: &VerifyOptions) -> Result<(), String> {
+    let VerifyOptions { network, rpc_url, expected, explain_no_code, rpc_timeout, rpc_retries, verify_onchain_time, json, redact_addresses } = *options;
+    let expected = load_expected_code_hashes(expected)?;
+    let rpc = rpc::RpcClient::new(rpc_url)?
+        .with_timeout(rpc_timeout)
+        .with_retries(rpc_retries);
+    let mut checks = verify_code_hashes(ctx, network, &rpc, &expected, explain_no_code, verify_onchain_time)?;
+    if redact_addresses {
+        for check in &mut checks {
+            check.error = check.error.take().map(|error| error.replace(&check.address, REDACTED_ADDRESS));
+            check.address = REDACTED_ADDRESS.to_string();
+        }
+    }
+
+    if json {
+        println!("{}", to_json(&checks)?);
+    } else {
+        let mut table = Table::new();
+        table.set_format(create_sui_style_format());
+        let mut header = row![bF-> "Contract", bF-> "Address", bF-> "Expected", bF-> "Actual", bF-> "Result"];
+        if verify_onchain_time {
+            header.add_cell(cell!(bF-> "File Modified"));
+            header.add_cell(cell!(bF-> "Mined On-Chain"));
+        }
+        table.add_row(header);
+        for check in &checks {
+            let status = match check.status {
+                CodeHashStatus::Match => cell!(Fg-> check.status.as_str()),
+                CodeHashStatus::Unchecked => cell!(check.status.as_str()),
+                CodeHashStatus::RpcError => cell!(Fy-> check.status.as_str()),
+                _ => cell!(Fr-> check.status.as_str()),
+            };
+            let mut row = Row::new(vec![
+                cell!(check.contract),
+                cell!(check.address),
+                cell!(check.expected.as_deref().unwrap_or("-")),
+                cell!(check.actual.as_deref().unwrap_or("-")),
+                status,
+            ]);
+            if verify_onchain_time {
+                let file_modified = check.file_modified.map_or("-".to_string(), format_timestamp);
+                // A file written before its contract was mined was copied or
+                // restored from somewhere else
+                match (check.file_modified, check.mined_at) {
+                    (Some(modified), Some(mined)) if modified < mined => row.add_cell(cell!(Fy-> file_modified)),
+                    _ => row.add_cell(cell!(file_modified)),
+                }
+                row.add_cell(cell!(check.mined_at.map_or("-".to_string(), format_timestamp)));
             }
-            fs::write(path, output).map_err(|e| format!("Failed to write to file: {}", e))?;
+            table.add_row(row);
+        }
+        table.printstd();
+
+        for check in &checks {
+            if let Some(cause) = check.no_code_cause {
+                println!("No code at {} ({}): {}", check.address, check.contract, cause.description());
+            }
+            if let Some(error) = &check.error {
+                println!("RPC error for {} ({}): {}", check.address, check.contract, error);
+            }
+        }
+    }
+
+    let rpc_errors = checks.iter().filter(|check| check.status == CodeHashStatus::RpcError).count();
+    let failed = checks.iter().filter(|check| check.status.failed()).count() - rpc_errors;
+    match (failed, rpc_errors) {
+        (0, 0) => Ok(()),
+        (failed, 0) => Err(format!("{} contract(s) on {} don't match their expected code hash", failed, network)),
+        (0, rpc_errors) => Err(format!("{} contract(s) on {} couldn't be checked because of RPC errors", rpc_errors, network)),
+        (failed, rpc_errors) => Err(format!("{} contract(s) on {} don't match their expected code hash, and {} couldn't be checked because of RPC errors", failed, network, rpc_errors)),
+    }
+}
+
+fn diff_contract(ctx: &Context, contract: &str, from: &str, to: &str, only_changes: bool, json: bool, redact_addresses: bool) -> Result<(), String> {
+    let mut changes = diff_contract_addresses(ctx, contract, from, to)?;
+    let deployed = !changes.is_empty();
+    if only_changes {
+        changes.retain(|change| change.change != AddressChangeKind::Unchanged);
+    }
+    if redact_addresses {
+        for change in &mut changes {
+            for address in [&mut change.from, &mut change.to].into_iter().flatten() {
+                *address = REDACTED_ADDRESS.to_string();
+            }
+        }
+    }
+
+    if json {
+        println!("{}", to_json(&changes)?);
+    } else if !deployed {
+        println!("Contract {} isn't deployed to any configured network at {} or {}", contract, from, to);
+    } else if changes.is_empty() {
+        println!("The address of {} didn't change on any network from {} to {}", contract, from, to);
+    } else {
+        println!("Address of {} from {} to {}:", contract, from, to);
+        let mut table = Table::new();
+        table.set_format(create_sui_style_format());
+        table.add_row(row![bF-> "Network", bF-> from, bF-> to, bF-> "Change"]);
+        for change in changes {
+            table.add_row(row![
+                camel_to_title_case(&change.network),
+                change.from.as_deref().unwrap_or("-"),
+                change.to.as_deref().unwrap_or("-"),
+                change.change.as_str()
+            ]);
+        }
+        table.printstd();
+    }
+
+    Ok(())
+}
+
+fn compare_configs(ctx: &Context, other: &Path, json: bool, outfile: Option<&Path>, pretty_numbers: bool) -> Result<(), String> {
+    let changes = compare_config_networks(ctx, other)?;
+    let from = other.display().to_string();
+    let to = ctx.config_name();
+
+    if json {
+        let output = to_json(&changes)?;
+        if let Some(path) = outfile {
+            write_output(path, output)?;
+        } else {
+            println!("{}", output);
+        }
+    } else if changes.is_empty() {
+        println!("{} and {} declare the same networks", from, to);
+    } else {
+        println!("Networks changed from {} to {}:", from, to);
+        let mut table = Table::new();
+        table.set_format(create_sui_style_format());
+        table.add_row(row![bF-> "Network", bF-> from, bF-> to, bF-> "Change"]);
+        for change in changes {
+            table.add_row(row![
+                camel_to_title_case(&change.network),
+                change.from.map_or("-".to_string(), |chain_id| format_chain_id(chain_id, pretty_numbers)),
+                change.to.map_or("-".to_string(), |chain_id| format_chain_id(chain_id, pretty_numbers)),
+                change.change.as_str()
+            ]);
+        }
+        table.printstd();
+    }
+
+    Ok(())
+}
+
+struct AuditOptions<'a> {
+    json: bool,
+    csv: bool,
+    csv_bom: bool,
+    csv_delimiter: char,
+    sarif: bool,
+    outfile: Option<&'a Path>,
+    summary_only: bool,
+    forbid_chains_below: Option<u64>,
+    fail_on_malformed: bool,
+    max_age: Option<Duration>,
+    expected_chains: Option<&'a Path>,
+    check_selectors: bool,
+    pretty_numbers: bool,
+    redact_addresses: bool,
+    page: Option<usize>,
+    page_size: usize,
+}
+
+fn audit_deployments(ctx: &Context, options: &AuditOptions) -> Result<(), String> {
+    let AuditOptions { json, sarif, outfile, summary_only, forbid_chains_below, fail_on_malformed, max_age, expected_chains, check_selectors, redact_addresses, .. } = *options;
+    let expected_chains = expected_chains.map(load_chain_ids).transpose()?;
+    let mut report = build_audit_report(ctx, forbid_chains_below, max_age, expected_chains.as_deref(), check_selectors)?;
+    if redact_addresses {
+        for padded in &mut report.padded_addresses {
+            padded.address = REDACTED_ADDRESS.to_string();
+        }
+        for malformed in &mut report.malformed_addresses {
+            malformed.address = REDACTED_ADDRESS.to_string();
+        }
+    }
+    if summary_only {
+        render_audit_summary(&report, json, outfile)?;
+    } else if sarif {
+        let output = to_json(&audit_sarif(ctx, &report)?)?;
+        if let Some(path) = outfile {
+            write_output(path, output)?;
+        } else {
+            println!("{}", output);
+        }
+    } else {
+        ctx.timings.time("rendering", || render_audit(ctx, &report, options))?;
+    }
+
+    if fail_on_malformed {
+        report_malformed_addresses(report.malformed_addresses.iter().map(|entry| (entry.directory.as_str(), entry.contract.as_str(), entry.address.as_str())), redact_addresses)?;
+    }
+
+    match forbid_chains_below {
+        Some(floor) if !report.chains_below_floor.is_empty() => Err(format!(
+            "Found {} deployment(s) on chains below chain ID {}",
+            report.chains_below_floor.len(),
+            floor
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Rules reported in SARIF output, as (rule ID, description)
+const AUDIT_RULES: &[(&str, &str)] = &[
+    ("config-without-deployment", "Network in the hardhat config has no deployment"),
+    ("deployment-without-config", "Deployment has no network in the hardhat config"),
+    ("similar-network-names", "Network names differ only by case or whitespace"),
+    ("chain-id-mismatch", "Deployment records a different chain ID than its directory"),
+    ("chain-below-floor", "Deployment is on a chain below the --forbid-chains-below floor"),
+    ("checksum-inconsistency", "Deployment mixes EIP-55 checksummed and non-checksummed addresses"),
+    ("old-deployment", "Deployment is older than the --max-age threshold and may be stale"),
+    ("expected-chain-without-deployment", "Chain in the --expected-chains list has no deployment"),
+    ("deployment-not-expected", "Deployment is on a chain missing from the --expected-chains list"),
+    ("selector-collision", "Contracts deployed on the same network share a function selector"),
+    ("padded-address", "Address is recorded with leading or trailing whitespace"),
+    ("malformed-address", "Address isn't a valid address or doesn't match its EIP-55 checksum"),
+];
+
+/// Builds a SARIF 2.1.0 log of the audit findings. Config findings point at
+/// the hardhat config, at the line declaring the network when it can be found,
+/// and chain ID mismatches point at the deployment directory.
+fn audit_sarif(ctx: &Context, report: &AuditReport) -> Result<Value, String> {
+    let config = ctx.read_config()?;
+    // URIs are relative to the project root, so code scanning can attach
+    // the findings to files in the repository
+    let uri = |path: &Path| {
+        let path = path.strip_prefix(&ctx.root).unwrap_or(path);
+        path.to_string_lossy().replace('\\', "/").trim_start_matches("./").to_string()
+    };
+    let config_uri = uri(&ctx.config_path().unwrap_or_else(|| ctx.root.join(ctx.config_name())));
+
+    let location = |network: Option<&str>| {
+        let line = network.and_then(|network| {
+            config.lines().position(|line| {
+                line.trim_start().strip_prefix(network)
+                    .is_some_and(|rest| rest.trim_start().starts_with(':'))
+            })
+        });
+        let mut physical_location = json!({ "artifactLocation": { "uri": config_uri } });
+        if let Some(line) = line {
+            physical_location["region"] = json!({ "startLine": line + 1 });
+        }
+        json!({ "physicalLocation": physical_location })
+    };
+    let deployment_location = |directory: &str, file: Option<&str>| {
+        let mut path = ctx.root.join("deployments").join(directory);
+        if let Some(file) = file {
+            path.push(file);
+        }
+        json!({ "physicalLocation": { "artifactLocation": { "uri": uri(&path) } } })
+    };
+
+    let mut results = Vec::new();
+    for missing in &report.config_without_deployment {
+        results.push(json!({
+            "ruleId": "config-without-deployment",
+            "level": "warning",
+            "message": { "text": format!("Network {} (chain ID {}) has no deployment in deployments/chain-{}", missing.network, missing.chain_id, missing.chain_id) },
+            "locations": [location(Some(&missing.network))],
+        }));
+    }
+    for deployment in &report.deployment_without_config {
+        let id = deployment.chain_id;
+        let probable_network = deployment.probable_network.as_ref().map(|name| format!(" (probably {})", name)).unwrap_or_default();
+        results.push(json!({
+            "ruleId": "deployment-without-config",
+            "level": "warning",
+            "message": { "text": format!("Deployment deployments/chain-{}{} has no network with chain ID {} in the hardhat config", id, probable_network, id) },
+            "locations": [location(None)],
+        }));
+    }
+    for group in &report.similar_network_names {
+        results.push(json!({
+            "ruleId": "similar-network-names",
+            "level": "warning",
+            "message": { "text": format!("Network names {} differ only by case or whitespace", group.networks.join(", ")) },
+            "locations": group.networks.iter().map(|network| location(Some(network))).collect::<Vec<_>>(),
+        }));
+    }
+
+    for id in &report.chains_below_floor {
+        results.push(json!({
+            "ruleId": "chain-below-floor",
+            "level": "error",
+            "message": { "text": format!("Deployment deployments/chain-{} is on a chain below the allowed chain ID floor", id) },
+            "locations": [deployment_location(&format!("chain-{}", id), None)],
+        }));
+    }
+    for mismatch in &report.chain_id_mismatches {
+        results.push(json!({
+            "ruleId": "chain-id-mismatch",
+            "level": "error",
+            "message": { "text": format!("Deployment in deployments/{} records chain ID {}", mismatch.directory, mismatch.recorded_chain_id) },
+            "locations": [deployment_location(&mismatch.directory, None)],
+        }));
+    }
+    for inconsistency in &report.checksum_inconsistencies {
+        results.push(json!({
+            "ruleId": "checksum-inconsistency",
+            "level": "note",
+            "message": { "text": format!("Deployment in deployments/{} has non-checksummed addresses for {} while others are checksummed", inconsistency.directory, inconsistency.non_checksummed.join(", ")) },
+            "locations": [deployment_location(&inconsistency.directory, Some("deployed_addresses.json"))],
+        }));
+    }
+    for old in &report.old_deployments {
+        results.push(json!({
+            "ruleId": "old-deployment",
+            "level": "warning",
+            "message": { "text": format!("Deployment in deployments/{} is {} day(s) old and may be stale", old.directory, old.age_days) },
+            "locations": [deployment_location(&old.directory, Some("deployed_addresses.json"))],
+        }));
+    }
+    for id in &report.expected_without_deployment {
+        results.push(json!({
+            "ruleId": "expected-chain-without-deployment",
+            "level": "warning",
+            "message": { "text": format!("Expected chain {} has no deployment in deployments/chain-{}", id, id) },
+            "locations": [deployment_location(&format!("chain-{}", id), None)],
+        }));
+    }
+    for id in &report.deployment_not_expected {
+        results.push(json!({
+            "ruleId": "deployment-not-expected",
+            "level": "warning",
+            "message": { "text": format!("Deployment deployments/chain-{} is on a chain missing from the expected chains list", id) },
+            "locations": [deployment_location(&format!("chain-{}", id), None)],
+        }));
+    }
+    for collision in &report.selector_collisions {
+        let functions: Vec<String> = collision.functions.iter()
+            .map(|function| format!("{}.{}", function.contract, function.signature))
+            .collect();
+        results.push(json!({
+            "ruleId": "selector-collision",
+            "level": "warning",
+            "message": { "text": format!("Selector {} in deployments/{} is shared by {}", collision.selector, collision.directory, functions.join(", ")) },
+            "locations": [deployment_location(&collision.directory, Some("deployed_addresses.json"))],
+        }));
+    }
+    for padded in &report.padded_addresses {
+        results.push(json!({
+            "ruleId": "padded-address",
+            "level": "warning",
+            "message": { "text": format!("Address of {} in deployments/{} has surrounding whitespace", padded.contract, padded.directory) },
+            "locations": [deployment_location(&padded.directory, Some("deployed_addresses.json"))],
+        }));
+    }
+    for malformed in &report.malformed_addresses {
+        results.push(json!({
+            "ruleId": "malformed-address",
+            "level": "error",
+            "message": { "text": format!("Address {} of {} in deployments/{} is malformed", malformed.address, malformed.contract, malformed.directory) },
+            "locations": [deployment_location(&malformed.directory, Some("deployed_addresses.json"))],
+        }));
+    }
+
+    Ok(json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "evm-deployment-info",
+                    "version": VERSION,
+                    "informationUri": "https://github.com/HenryMBaldwin/evm-deployment-info-cli",
+                    "rules": AUDIT_RULES.iter().map(|(id, description)| json!({
+                        "id": id,
+                        "shortDescription": { "text": description },
+                    })).collect::<Vec<_>>(),
+                }
+            },
+            "results": results,
+        }]
+    }))
+}
+
+fn render_audit_summary(report: &AuditReport, json: bool, outfile: Option<&Path>) -> Result<(), String> {
+    let summary = report.summary();
+
+    if json {
+        let output = to_json(&summary)?;
+        if let Some(path) = outfile {
+            write_output(path, output)?;
+        } else {
+            println!("{}", output);
+        }
+    } else {
+        let mut counts = vec![
+            format!("{} config(s) without deployments", summary.config_without_deployment),
+            format!("{} deployment(s) without config", summary.deployment_without_config),
+            format!("{} group(s) of similar network names", summary.similar_network_names),
+            format!("{} chain ID mismatch(es)", summary.chain_id_mismatches),
+        ];
+        if summary.chains_below_floor > 0 {
+            counts.push(format!("{} deployment(s) below the chain ID floor", summary.chains_below_floor));
+        }
+        counts.push(format!("{} deployment(s) with inconsistent checksums", summary.checksum_inconsistencies));
+        if summary.old_deployments > 0 {
+            counts.push(format!("{} deployment(s) older than the maximum age", summary.old_deployments));
+        }
+        if summary.expected_without_deployment > 0 {
+            counts.push(format!("{} expected chain(s) without deployments", summary.expected_without_deployment));
+        }
+        if summary.deployment_not_expected > 0 {
+            counts.push(format!("{} deployment(s) on unexpected chains", summary.deployment_not_expected));
+        }
+        if summary.selector_collisions > 0 {
+            counts.push(format!("{} function selector collision(s)", summary.selector_collisions));
+        }
+        if summary.padded_addresses > 0 {
+            counts.push(format!("{} address(es) with surrounding whitespace", summary.padded_addresses));
+        }
+        if summary.malformed_addresses > 0 {
+            counts.push(format!("{} malformed address(es)", summary.malformed_addresses));
+        }
+        println!("{}", counts.join(", "));
+    }
+
+    Ok(())
+}
+
+/// Prints the audit findings. `--page` selects a slice of the table output;
+/// JSON and CSV always include every finding.
+fn render_audit(ctx: &Context, report: &AuditReport, options: &AuditOptions) -> Result<(), String> {
+    let AuditOptions { json, csv, csv_bom, csv_delimiter, outfile, pretty_numbers, page, page_size, .. } = *options;
+    let page = page.map(|page| (page, page_size));
+    let AuditReport { config_without_deployment, deployment_without_config, similar_network_names, chain_id_mismatches, chains_below_floor, checksum_inconsistencies, old_deployments, expected_without_deployment, deployment_not_expected, selector_collisions, padded_addresses, malformed_addresses } = report;
+
+    if json {
+        let output = to_json(report)?;
+        if let Some(path) = outfile {
+            write_output(path, output)?;
         } else {
             println!("{}", output);
         }
     } else if csv {
-        let mut csv_content = String::new();
-        
-        csv_content.push_str("Configs Without Deployments\nNetwork,Chain ID\n");
-        for (name, id) in &config_without_deployment {
-            csv_content.push_str(&format!("{},{}\n", name, id));
+        let mut csv_content = if csv_bom { String::from('\u{feff}') } else { String::new() };
+        // Sections after the first are separated by a blank line
+        let mut first = true;
+        let mut section = |title: &str, header: &[&str], rows: Vec<Vec<String>>| {
+            if !std::mem::take(&mut first) {
+                csv_content.push('\n');
+            }
+            csv_content.push_str(&csv_row(&[title], csv_delimiter));
+            csv_content.push_str(&csv_row(header, csv_delimiter));
+            for row in rows {
+                csv_content.push_str(&csv_row(&row, csv_delimiter));
+            }
+        };
+
+        section("Configs Without Deployments", &["Network", "Chain ID"], config_without_deployment.iter()
+            .map(|missing| vec![missing.network.clone(), missing.chain_id.to_string()])
+            .collect());
+
+        section("Deployments Without Configs", &["Chain ID", "Probable Network"], deployment_without_config.iter()
+            .map(|deployment| vec![deployment.chain_id.to_string(), deployment.probable_network.clone().unwrap_or_else(|| "-".to_string())])
+            .collect());
+
+        section("Network Names Differing Only By Case", &["Normalized Name", "Network"], similar_network_names.iter()
+            .flat_map(|group| group.networks.iter().map(|name| vec![group.normalized.clone(), name.clone()]))
+            .collect());
+
+        section("Chain ID Mismatches", &["Directory", "Recorded Chain ID"], chain_id_mismatches.iter()
+            .map(|mismatch| vec![mismatch.directory.clone(), mismatch.recorded_chain_id.to_string()])
+            .collect());
+
+        if !chains_below_floor.is_empty() {
+            section("Deployments Below Chain ID Floor", &["Chain ID"], chains_below_floor.iter()
+                .map(|id| vec![id.to_string()])
+                .collect());
         }
-        
-        csv_content.push_str("\nDeployments Without Configs\nChain ID\n");
-        for id in &deployment_without_config {
-            csv_content.push_str(&format!("{}\n", id));
+
+        section("Checksum Inconsistencies", &["Directory", "Non-Checksummed Contract"], checksum_inconsistencies.iter()
+            .flat_map(|inconsistency| inconsistency.non_checksummed.iter().map(|contract| vec![inconsistency.directory.clone(), contract.clone()]))
+            .collect());
+
+        if !old_deployments.is_empty() {
+            section("Deployments Older Than Max Age", &["Directory", "Age (Days)"], old_deployments.iter()
+                .map(|old| vec![old.directory.clone(), old.age_days.to_string()])
+                .collect());
+        }
+
+        if !expected_without_deployment.is_empty() {
+            section("Expected Chains Without Deployments", &["Chain ID", "Probable Network"], expected_without_deployment.iter()
+                .map(|id| vec![id.to_string(), ctx.chain_name(*id).unwrap_or("-").to_string()])
+                .collect());
+        }
+
+        if !deployment_not_expected.is_empty() {
+            section("Deployments On Unexpected Chains", &["Chain ID", "Probable Network"], deployment_not_expected.iter()
+                .map(|id| vec![id.to_string(), ctx.chain_name(*id).unwrap_or("-").to_string()])
+                .collect());
+        }
+
+        if !selector_collisions.is_empty() {
+            section("Function Selector Collisions", &["Directory", "Selector", "Contract", "Function"], selector_collisions.iter()
+                .flat_map(|collision| collision.functions.iter().map(|function| vec![collision.directory.clone(), collision.selector.clone(), function.contract.clone(), function.signature.clone()]))
+                .collect());
+        }
+
+        if !padded_addresses.is_empty() {
+            section("Addresses With Surrounding Whitespace", &["Directory", "Contract"], padded_addresses.iter()
+                .map(|padded| vec![padded.directory.clone(), padded.contract.clone()])
+                .collect());
+        }
+
+        if !malformed_addresses.is_empty() {
+            section("Malformed Addresses", &["Directory", "Contract", "Address"], malformed_addresses.iter()
+                .map(|malformed| vec![malformed.directory.clone(), malformed.contract.clone(), malformed.address.clone()])
+                .collect());
         }
 
         if let Some(path) = outfile {
-            if let Some(parent) = path.parent() {
-                fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+            write_output(path, csv_content)?;
+        } else {
+            print!("{}", csv_content);
+        }
+    } else {
+        let total = config_without_deployment.len() + deployment_without_config.len() + similar_network_names.len()
+            + chain_id_mismatches.len() + chains_below_floor.len() + checksum_inconsistencies.len() + old_deployments.len()
+            + expected_without_deployment.len() + deployment_not_expected.len() + selector_collisions.len()
+            + padded_addresses.len() + malformed_addresses.len();
+        let pages = page.map(|(_, page_size)| total.div_ceil(page_size).max(1));
+        if let (Some((page, _)), Some(pages)) = (page, pages) {
+            if page > pages {
+                return Err(format!("Page {} is out of range, there are {} page(s)", page, pages));
+            }
+        }
+
+        // Findings are paged as one list running through every section;
+        // `window` returns the part of the next section on the page
+        let mut offset = 0;
+        let mut window = |len: usize| {
+            let rows = match page {
+                Some((page, page_size)) => {
+                    let start = (page - 1) * page_size;
+                    start.saturating_sub(offset).min(len)..(start + page_size).saturating_sub(offset).min(len)
+                }
+                None => 0..len,
+            };
+            offset += len;
+            rows
+        };
+
+        let rows = window(config_without_deployment.len());
+        if !rows.is_empty() {
+            println!("\nFound {} network(s) in config without deployments:", config_without_deployment.len());
+            let mut table = Table::new();
+            table.set_format(create_sui_style_format());
+            table.add_row(row![bF-> "Network", bF-> "Chain ID"]);
+            for missing in &config_without_deployment[rows] {
+                table.add_row(row![missing.network, format_chain_id(missing.chain_id, pretty_numbers)]);
+            }
+            table.printstd();
+        }
+
+        let rows = window(deployment_without_config.len());
+        if !rows.is_empty() {
+            println!("\nFound {} deployment(s) without config entries:", deployment_without_config.len());
+            let mut table = Table::new();
+            table.set_format(create_sui_style_format());
+            table.add_row(row![bF-> "Chain ID", bF-> "Probable Network", bF-> "Chain List"]);
+            
+            for deployment in &deployment_without_config[rows] {
+                table.add_row(row![
+                    format_chain_id(deployment.chain_id, pretty_numbers),
+                    deployment.probable_network.as_deref().unwrap_or("-"),
+                    Fb-> format!("https://chainlist.org/chain/{}", deployment.chain_id)
+                ]);
+            }
+            table.printstd();
+        }
+
+        let rows = window(similar_network_names.len());
+        if !rows.is_empty() {
+            println!("\nFound {} group(s) of network names differing only by case or whitespace:", similar_network_names.len());
+            let mut table = Table::new();
+            table.set_format(create_sui_style_format());
+            table.add_row(row![bF-> "Networks"]);
+            for group in &similar_network_names[rows] {
+                table.add_row(row![group.networks.join(", ")]);
+            }
+            table.printstd();
+        }
+
+        let rows = window(chain_id_mismatches.len());
+        if !rows.is_empty() {
+            println!("\nFound {} deployment(s) recording a different chain ID than their directory:", chain_id_mismatches.len());
+            let mut table = Table::new();
+            table.set_format(create_sui_style_format());
+            table.add_row(row![bF-> "Directory", bF-> "Recorded Chain ID"]);
+            for mismatch in &chain_id_mismatches[rows] {
+                table.add_row(row![mismatch.directory, Fr-> format_chain_id(mismatch.recorded_chain_id, pretty_numbers)]);
+            }
+            table.printstd();
+        }
+
+        let rows = window(chains_below_floor.len());
+        if !rows.is_empty() {
+            println!("\nFound {} deployment(s) on chains below the allowed chain ID floor:", chains_below_floor.len());
+            let mut table = Table::new();
+            table.set_format(create_sui_style_format());
+            table.add_row(row![bF-> "Directory"]);
+            for id in &chains_below_floor[rows] {
+                table.add_row(row![Fr-> format!("chain-{}", id)]);
+            }
+            table.printstd();
+        }
+
+        let rows = window(checksum_inconsistencies.len());
+        if !rows.is_empty() {
+            println!("\nFound {} deployment(s) mixing checksummed and non-checksummed addresses:", checksum_inconsistencies.len());
+            let mut table = Table::new();
+            table.set_format(create_sui_style_format());
+            table.add_row(row![bF-> "Directory", bF-> "Non-Checksummed Contracts"]);
+            for inconsistency in &checksum_inconsistencies[rows] {
+                table.add_row(row![inconsistency.directory, Fy-> inconsistency.non_checksummed.join(", ")]);
+            }
+            table.printstd();
+        }
+
+        let rows = window(old_deployments.len());
+        if !rows.is_empty() {
+            println!("\nFound {} deployment(s) older than the maximum age, potentially stale:", old_deployments.len());
+            let mut table = Table::new();
+            table.set_format(create_sui_style_format());
+            table.add_row(row![bF-> "Directory", bF-> "Age"]);
+            for old in &old_deployments[rows] {
+                table.add_row(row![old.directory, Fy-> format!("{} day(s)", old.age_days)]);
+            }
+            table.printstd();
+        }
+
+        let rows = window(expected_without_deployment.len());
+        if !rows.is_empty() {
+            println!("\nFound {} expected chain(s) without deployments:", expected_without_deployment.len());
+            let mut table = Table::new();
+            table.set_format(create_sui_style_format());
+            table.add_row(row![bF-> "Chain ID", bF-> "Probable Network"]);
+            for id in &expected_without_deployment[rows] {
+                table.add_row(row![Fy-> format_chain_id(*id, pretty_numbers), ctx.chain_name(*id).unwrap_or("-")]);
+            }
+            table.printstd();
+        }
+
+        let rows = window(deployment_not_expected.len());
+        if !rows.is_empty() {
+            println!("\nFound {} deployment(s) on chains not in the expected chains list:", deployment_not_expected.len());
+            let mut table = Table::new();
+            table.set_format(create_sui_style_format());
+            table.add_row(row![bF-> "Chain ID", bF-> "Probable Network"]);
+            for id in &deployment_not_expected[rows] {
+                table.add_row(row![Fy-> format_chain_id(*id, pretty_numbers), ctx.chain_name(*id).unwrap_or("-")]);
+            }
+            table.printstd();
+        }
+
+        let rows = window(selector_collisions.len());
+        if !rows.is_empty() {
+            println!("\nFound {} function selector(s) shared by contracts on the same network:", selector_collisions.len());
+            let mut table = Table::new();
+            table.set_format(create_sui_style_format());
+            table.add_row(row![bF-> "Directory", bF-> "Selector", bF-> "Functions"]);
+            for collision in &selector_collisions[rows] {
+                let functions: Vec<String> = collision.functions.iter()
+                    .map(|function| format!("{}: {}", function.contract, function.signature))
+                    .collect();
+                table.add_row(row![collision.directory, Fy-> collision.selector, functions.join("\n")]);
             }
-            fs::write(path, csv_content).map_err(|e| format!("Failed to write to file: {}", e))?;
-        } else {
-            print!("{}", csv_content);
+            table.printstd();
         }
-    } else {
-        if !config_without_deployment.is_empty() {
-            println!("\nFound {} network(s) in config without deployments:", config_without_deployment.len());
+
+        let rows = window(padded_addresses.len());
+        if !rows.is_empty() {
+            println!("\nFound {} address(es) recorded with surrounding whitespace:", padded_addresses.len());
             let mut table = Table::new();
             table.set_format(create_sui_style_format());
-            table.add_row(row![bF-> "Network", bF-> "Chain ID"]);
-            for (name, id) in config_without_deployment {
-                table.add_row(row![name, id]);
+            table.add_row(row![bF-> "Directory", bF-> "Contract", bF-> "Address"]);
+            for padded in &padded_addresses[rows] {
+                table.add_row(row![padded.directory, padded.contract, Fy-> format!("{:?}", padded.address)]);
             }
             table.printstd();
         }
 
-        if !deployment_without_config.is_empty() {
-            println!("\nFound {} deployment(s) without config entries:", deployment_without_config.len());
+        let rows = window(malformed_addresses.len());
+        if !rows.is_empty() {
+            println!("\nFound {} malformed address(es):", malformed_addresses.len());
             let mut table = Table::new();
             table.set_format(create_sui_style_format());
-            table.add_row(row![bF-> "Chain ID", bF-> "Chain List"]);
-            
-            for id in deployment_without_config {
-                table.add_row(row![
-                    id,
-                    Fb-> format!("https://chainlist.org/chain/{}", id)
-                ]);
+            table.add_row(row![bF-> "Directory", bF-> "Contract", bF-> "Address"]);
+            for malformed in &malformed_addresses[rows] {
+                table.add_row(row![malformed.directory, malformed.contract, Fr-> malformed.address]);
             }
             table.printstd();
         }
+
+        if let (Some((page, _)), Some(pages)) = (page, pages) {
+            println!("\nPage {} of {}", page, pages);
+        }
     }
 
     Ok(())
@@ -758,34 +2497,35 @@ fn check_install_permissions() -> bool {
     }
 }
 
-fn coverage_deployments(root: &Path, json: bool, csv: bool, outfile: Option<&Path>) -> Result<(), String> {
-    let networks = parse_hardhat_config(root)?;
-    let deployments_dir = root.join("deployments");
+fn coverage_deployments(ctx: &Context, json: bool, csv: bool, outfile: Option<&Path>) -> Result<(), String> {
+    let networks = parse_hardhat_config(ctx)?;
+    let deployments_dir = deployments_dir(ctx)?;
     
     // Group deployments by ecosystem
     let mut ecosystems: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+    let mut configured_networks = 0;
+    let mut deployed_networks = 0;
     
     for (network_name, chain_id) in networks {
         if network_name == "hardhat" {
             continue;
         }
+        configured_networks += 1;
 
         let chain_dir = deployments_dir.join(format!("chain-{}", chain_id));
-        if let Ok(Some(address)) = get_deployment_address(&chain_dir) {
-            let parts: Vec<&str> = network_name.split(|c: char| c.is_uppercase()).collect();
-            let prefix = parts[0].to_string();
-            let suffix = network_name[prefix.len()..].to_string();
-            
-            let suffix = if suffix.is_empty() {
-                "Mainnet".to_string()
-            } else {
-                suffix
-            };
-            
-            ecosystems
-                .entry(prefix)
-                .or_default()
-                .insert(suffix, address);
+        match get_deployment_address(ctx, &chain_dir) {
+            Ok(Some(address)) => {
+                deployed_networks += 1;
+                let (prefix, suffix) = split_network_name(&network_name);
+                ecosystems
+                    .entry(prefix)
+                    .or_default()
+                    .insert(suffix, address);
+            }
+            Ok(None) => {}
+            Err(e) => {
+                ctx.errors.network_error(chain_id, format!("Error reading deployment for {}: {}", network_name, e))?;
+            }
         }
     }
 
@@ -819,9 +2559,9 @@ fn coverage_deployments(root: &Path, json: bool, csv: bool, outfile: Option<&Pat
         output.insert("mainnet_coverage".to_string(), json!(mainnet_coverage));
         output.insert("testnet_coverage".to_string(), json!(testnet_coverage));
 
-        let output = serde_json::to_string_pretty(&output).map_err(|e| e.to_string())?;
+        let output = to_json(&output)?;
         if let Some(path) = outfile {
-            fs::write(path, output).map_err(|e| format!("Failed to write to file: {}", e))?;
+            write_output(path, output)?;
         } else {
             println!("{}", output);
         }
@@ -829,24 +2569,24 @@ fn coverage_deployments(root: &Path, json: bool, csv: bool, outfile: Option<&Pat
         let mut csv_content = String::from("Category,Network\n");
         
         for ecosystem in &mainnet_only {
-            csv_content.push_str(&format!("Mainnet Only,{}\n", camel_to_title_case(ecosystem)));
+            csv_content.push_str(&csv_row(&["Mainnet Only".to_string(), camel_to_title_case(ecosystem)], ','));
         }
         
         for ecosystem in &testnet_only {
-            csv_content.push_str(&format!("Testnet Only,{}\n", camel_to_title_case(ecosystem)));
+            csv_content.push_str(&csv_row(&["Testnet Only".to_string(), camel_to_title_case(ecosystem)], ','));
         }
         
         for ecosystem in &both {
-            csv_content.push_str(&format!("Both,{}\n", camel_to_title_case(ecosystem)));
+            csv_content.push_str(&csv_row(&["Both".to_string(), camel_to_title_case(ecosystem)], ','));
         }
 
-        csv_content.push_str(&format!("\nCoverage Statistics\n"));
-        csv_content.push_str(&format!("Metric,Percentage\n"));
+        csv_content.push_str("\nCoverage Statistics\n");
+        csv_content.push_str("Metric,Percentage\n");
         csv_content.push_str(&format!("Mainnet Coverage,{:.1}%\n", mainnet_coverage));
         csv_content.push_str(&format!("Testnet Coverage,{:.1}%\n", testnet_coverage));
 
         if let Some(path) = outfile {
-            fs::write(path, csv_content).map_err(|e| format!("Failed to write to file: {}", e))?;
+            write_output(path, csv_content)?;
         } else {
             print!("{}", csv_content);
         }
@@ -877,17 +2617,37 @@ fn coverage_deployments(root: &Path, json: bool, csv: bool, outfile: Option<&Pat
             println!();
         }
 
+        // Bars only make sense in a terminal, redirected output keeps the
+        // plain percentages
+        let bars = std::io::stdout().is_terminal();
+        let coverage = |percentage: f64| {
+            if bars {
+                format!("{} {:.1}%", coverage_bar(percentage, 20), percentage)
+            } else {
+                format!("{:.1}%", percentage)
+            }
+        };
+        let network_coverage = if configured_networks == 0 {
+            0.0
+        } else {
+            deployed_networks as f64 / configured_networks as f64 * 100.0
+        };
+
         println!("Coverage Statistics:");
         let mut table = Table::new();
         table.set_format(create_sui_style_format());
         table.add_row(row![bF-> "Metric", bF-> "Coverage"]);
+        table.add_row(row![
+            "Networks Deployed",
+            format!("{} ({} of {})", coverage(network_coverage), deployed_networks, configured_networks)
+        ]);
         table.add_row(row![
             "Mainnet Coverage",
-            format!("{:.1}%", mainnet_coverage)
+            coverage(mainnet_coverage)
         ]);
         table.add_row(row![
             "Testnet Coverage",
-            format!("{:.1}%", testnet_coverage)
+            coverage(testnet_coverage)
         ]);
         table.printstd();
     }
@@ -895,16 +2655,515 @@ fn coverage_deployments(root: &Path, json: bool, csv: bool, outfile: Option<&Pat
     Ok(())
 }
 
+/// Renders `percentage` as a bar of `width` cells, e.g. `[████░░░░]` for 50%.
+fn coverage_bar(percentage: f64, width: usize) -> String {
+    let filled = if percentage.is_finite() {
+        ((percentage / 100.0 * width as f64).round() as usize).min(width)
+    } else {
+        0
+    };
+    format!("[{}{}]", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+fn stale_deployments(ctx: &Context, json: bool, csv: bool, outfile: Option<&Path>) -> Result<(), String> {
+    let networks = parse_hardhat_config(ctx)?;
+    let deployments_dir = deployments_dir(ctx)?;
+
+    // (network, contract, status)
+    let mut results: Vec<(String, String, &str)> = Vec::new();
+
+    for (network_name, chain_id) in networks {
+        if network_name == "hardhat" {
+            continue;
+        }
+
+        let chain_dir = deployments_dir.join(format!("chain-{}", chain_id));
+        let contracts = match get_deployment_contracts(ctx, &chain_dir) {
+            Ok(Some(contracts)) => contracts,
+            Ok(None) => continue,
+            Err(e) => {
+                ctx.errors.network_error(chain_id, format!("Error reading deployment for {}: {}", network_name, e))?;
+                continue;
+            }
+        };
+
+        let deployed_at = ctx.modified(&chain_dir.join("deployed_addresses.json"))
+            .ok_or_else(|| format!("Failed to read deployment time for {}", network_name))?;
+
+        for (contract, _) in contracts {
+            let source_modified = resolve_contract_source(ctx, &chain_dir, &contract)
+                .and_then(|source| ctx.modified(&ctx.root.join(source)));
+
+            let status = match source_modified {
+                Some(modified) if modified > deployed_at => "Stale",
+                Some(_) => "Up to date",
+                None => "Unknown",
+            };
+            results.push((network_name.clone(), contract, status));
+        }
+    }
+
+    results.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+    let stale_count = results.iter().filter(|(_, _, status)| *status == "Stale").count();
+
+    if json {
+        let output = json!({
+            "stale_count": stale_count,
+            "deployments": results.iter().map(|(network, contract, status)| {
+                json!({
+                    "network": network,
+                    "contract": contract,
+                    "status": status
+                })
+            }).collect::<Vec<_>>()
+        });
+
+        let output = to_json(&output)?;
+        if let Some(path) = outfile {
+            write_output(path, output)?;
+        } else {
+            println!("{}", output);
+        }
+    } else if csv {
+        let mut csv_content = String::from("Network,Contract,Status\n");
+        for (network, contract, status) in &results {
+            csv_content.push_str(&csv_row(&[network.as_str(), contract.as_str(), status], ','));
+        }
+
+        if let Some(path) = outfile {
+            write_output(path, csv_content)?;
+        } else {
+            print!("{}", csv_content);
+        }
+    } else {
+        println!("Found {} stale deployment(s) out of {} contract(s):", stale_count, results.len());
+        let mut table = Table::new();
+        table.set_format(create_sui_style_format());
+        table.add_row(row![bF-> "Network", bF-> "Contract", bF-> "Status"]);
+        for (network, contract, status) in results {
+            let status_cell = match status {
+                "Stale" => cell!(Fr-> status),
+                "Up to date" => cell!(Fg-> status),
+                _ => cell!(status),
+            };
+            table.add_row(Row::new(vec![
+                cell!(camel_to_title_case(&network)),
+                cell!(contract),
+                status_cell,
+            ]));
+        }
+        table.printstd();
+    }
+
+    Ok(())
+}
+
+/// Reads the entries of a directory as (path, whether it's a directory),
+/// sorted by path so tree output is stable.
+fn sorted_dir_entries(ctx: &Context, dir: &Path) -> Result<Vec<(PathBuf, bool)>, String> {
+    Ok(ctx.list_dir(dir)?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, is_dir)| (dir.join(name), is_dir))
+        .collect())
+}
+
+/// Returns the chain ID, configured network names and address count for a
+/// `chain-<id>` directory, or `None` if the directory isn't a chain directory.
+fn chain_dir_info(ctx: &Context, path: &Path, chain_names: &HashMap<u64, Vec<String>>) -> Option<(u64, Vec<String>, usize)> {
+    let chain_id = path.file_name()?.to_str()?.strip_prefix("chain-")?.parse::<u64>().ok()?;
+    let names = chain_names.get(&chain_id).cloned().unwrap_or_default();
+    let address_count = get_deployment_contracts(ctx, path).ok().flatten().map_or(0, |contracts| contracts.len());
+    Some((chain_id, names, address_count))
+}
+
+fn print_tree(ctx: &Context, dir: &Path, prefix: &str, chain_names: &HashMap<u64, Vec<String>>, top_level: bool) -> Result<(), String> {
+    print_tree_entries(ctx, &sorted_dir_entries(ctx, dir)?, prefix, chain_names, top_level)
+}
+
+fn print_tree_entries(ctx: &Context, entries: &[(PathBuf, bool)], prefix: &str, chain_names: &HashMap<u64, Vec<String>>, top_level: bool) -> Result<(), String> {
+    for (i, (path, is_dir)) in entries.iter().enumerate() {
+        let last = i == entries.len() - 1;
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+        let mut label = if *is_dir { format!("{}/", name) } else { name };
+        if top_level && *is_dir {
+            if let Some((_, names, address_count)) = chain_dir_info(ctx, path, chain_names) {
+                let network = if names.is_empty() {
+                    "not in config".to_string()
+                } else {
+                    names.iter().map(|n| camel_to_title_case(n)).collect::<Vec<_>>().join(", ")
+                };
+                label = format!("{} ({}, {} address(es))", label, network, address_count);
+            }
+        }
+
+        println!("{}{}{}", prefix, if last { "└── " } else { "├── " }, label);
+        if *is_dir {
+            let child_prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+            print_tree(ctx, path, &child_prefix, chain_names, false)?;
+        }
+    }
+    Ok(())
+}
+
+fn build_tree_json(ctx: &Context, path: &Path, is_dir: bool, chain_names: &HashMap<u64, Vec<String>>, top_level: bool) -> Result<Value, String> {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    if !is_dir {
+        return Ok(json!({ "name": name, "type": "file" }));
+    }
+
+    let mut node = serde_json::Map::new();
+    node.insert("name".to_string(), json!(name));
+    node.insert("type".to_string(), json!("directory"));
+    if top_level {
+        if let Some((chain_id, names, address_count)) = chain_dir_info(ctx, path, chain_names) {
+            node.insert("chain_id".to_string(), json!(chain_id));
+            node.insert("networks".to_string(), json!(names));
+            node.insert("address_count".to_string(), json!(address_count));
+        }
+    }
+
+    let children = sorted_dir_entries(ctx, path)?
+        .iter()
+        .map(|(child, is_dir)| build_tree_json(ctx, child, *is_dir, chain_names, false))
+        .collect::<Result<Vec<_>, _>>()?;
+    node.insert("children".to_string(), Value::Array(children));
+
+    Ok(Value::Object(node))
+}
+
+fn tree_deployments(ctx: &Context, json: bool) -> Result<(), String> {
+    let networks = parse_hardhat_config(ctx)?;
+    let deployments_dir = deployments_dir(ctx)?;
+    if ctx.list_dir(&deployments_dir)?.is_none() {
+        return Err("No deployments directory found in the specified root directory".to_string());
+    }
+
+    let mut chain_names: HashMap<u64, Vec<String>> = HashMap::new();
+    for (network_name, chain_id) in networks {
+        if network_name == "hardhat" {
+            continue;
+        }
+        chain_names.entry(chain_id).or_default().push(network_name);
+    }
+    for names in chain_names.values_mut() {
+        names.sort();
+    }
+
+    let entries: Vec<(PathBuf, bool)> = sorted_dir_entries(ctx, &deployments_dir)?
+        .into_iter()
+        .filter(|(path, is_dir)| !(*is_dir && ctx.is_ignored_dir(path)))
+        .collect();
+
+    if json {
+        let children = entries
+            .iter()
+            .map(|(child, is_dir)| build_tree_json(ctx, child, *is_dir, &chain_names, true))
+            .collect::<Result<Vec<_>, _>>()?;
+        let output = json!({
+            "name": "deployments",
+            "type": "directory",
+            "children": children
+        });
+        println!("{}", to_json(&output)?);
+    } else {
+        println!("deployments/");
+        print_tree_entries(ctx, &entries, "", &chain_names, true)?;
+    }
+
+    Ok(())
+}
+
+fn open_deployment(ctx: &Context, network: &str, contract: Option<&str>) -> Result<(), String> {
+    let (chain_id, address) = resolve_deployment(ctx, network, contract)?;
+    let url = explorer::address_url(chain_id, &address)
+        .ok_or_else(|| format!("No known block explorer for chain ID {}", chain_id))?;
+
+    match webbrowser::open(&url) {
+        Ok(()) => println!("Opened {}", url),
+        Err(e) => {
+            eprintln!("Warning: Could not launch a browser ({}), printing URL instead", e);
+            println!("{}", url);
+        }
+    }
+
+    Ok(())
+}
+
+fn qr_deployment(ctx: &Context, network: &str, contract: Option<&str>, outfile: Option<&Path>) -> Result<(), String> {
+    let (_, address) = resolve_deployment(ctx, network, contract)?;
+    let code = qrcode::QrCode::new(address.as_bytes())
+        .map_err(|e| format!("Failed to encode {} as a QR code: {}", address, e))?;
+
+    if let Some(path) = outfile {
+        let is_png = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("png"));
+        if !is_png {
+            return Err(format!("QR codes can only be saved as PNG, got {}", path.display()));
+        }
+        let mut png = std::io::Cursor::new(Vec::new());
+        code.render::<image::Luma<u8>>()
+            .min_dimensions(256, 256)
+            .build()
+            .write_to(&mut png, image::ImageFormat::Png)
+            .map_err(|e| format!("Failed to encode the QR code as PNG: {}", e))?;
+        write_output(path, png.into_inner())?;
+        println!("Saved the QR code of {} to {}", address, path.display());
+    } else {
+        // Light modules on a dark background, so it scans on dark terminals too
+        let qr = code.render::<qrcode::render::unicode::Dense1x2>()
+            .dark_color(qrcode::render::unicode::Dense1x2::Light)
+            .light_color(qrcode::render::unicode::Dense1x2::Dark)
+            .build();
+        println!("{}", qr);
+        println!("{}", address);
+    }
+
+    Ok(())
+}
+
+/// Escapes `text` for use inside a quoted DOT string.
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Quotes `text` as a DOT identifier.
+fn dot_quote(text: &str) -> String {
+    format!("\"{}\"", dot_escape(text))
+}
+
+fn graph_deployments(ctx: &Context, network: Option<&str>, outfile: Option<&Path>, redact_addresses: bool) -> Result<(), String> {
+    let mut report = build_list_report_at(ctx, ContractSelection::All, None)?;
+    if redact_addresses {
+        report.redact_addresses();
+    }
+    if let Some(network) = network {
+        if !report.deployments.iter().any(|deployment| deployment.network == network) {
+            return Err(format!("No deployment found for network {}", network));
+        }
+    }
+
+    let mut dot = String::from("digraph deployments {\n    rankdir=LR;\n    node [shape=box];\n");
+    let mut links_found = 0;
+    for deployment in &report.deployments {
+        if network.is_some_and(|network| network != deployment.network) {
+            continue;
+        }
+        let links = proxy_links(deployment);
+        if links.is_empty() {
+            continue;
+        }
+        links_found += links.len();
+
+        // Node IDs are prefixed with the network, since the same contract
+        // usually exists on several networks
+        let node = |name: &str| dot_quote(&format!("{}/{}", deployment.network, name));
+        dot.push_str(&format!("\n    subgraph {} {{\n        label={};\n",
+            dot_quote(&format!("cluster_{}", deployment.network)),
+            dot_quote(&camel_to_title_case(&deployment.network))));
+        for contract in &deployment.contracts {
+            if links.iter().any(|link| link.from == contract.name || link.to == contract.name) {
+                // \n is DOT's line break inside a label
+                dot.push_str(&format!("        {} [label=\"{}\\n{}\"];\n",
+                    node(&contract.name),
+                    dot_escape(&contract.name),
+                    dot_escape(&contract.address)));
+            }
+        }
+        for link in &links {
+            let label = match link.role {
+                ProxyRole::Admin => "admin",
+                _ => "implementation",
+            };
+            dot.push_str(&format!("        {} -> {} [label={}];\n", node(&link.from), node(&link.to), dot_quote(label)));
+        }
+        dot.push_str("    }\n");
+    }
+    dot.push_str("}\n");
+
+    if links_found == 0 {
+        eprintln!("Warning: No proxies found (contracts are recognized by Proxy or ProxyAdmin in their name)");
+    }
+    if let Some(path) = outfile {
+        write_output(path, dot)?;
+    } else {
+        print!("{}", dot);
+    }
+
+    Ok(())
+}
+
+fn where_contract(ctx: &Context, network: &str, contract: Option<&str>, json: bool) -> Result<(), String> {
+    let location = locate_contract(ctx, network, contract)?;
+
+    if json {
+        println!("{}", to_json(&location)?);
+        return Ok(());
+    }
+
+    match &location.source {
+        Some(source) => println!("{}", source.display()),
+        None => eprintln!("Warning: Could not resolve the source of {} (no build-info in the deployment's artifacts)", location.contract),
+    }
+    match &location.artifact {
+        Some(artifact) => println!("{}", artifact.display()),
+        None => eprintln!("Warning: No artifact found for {}", location.contract),
+    }
+    if location.source.is_none() && location.artifact.is_none() {
+        return Err(format!("No source or artifact found for {} on {}", location.contract, network));
+    }
+
+    Ok(())
+}
+
+/// Prints the addresses a contract had on a network over time, oldest first.
+fn show_contract_history(ctx: &Context, network: &str, contract: &str, json: bool, redact_addresses: bool) -> Result<(), String> {
+    let mut history = contract_history(ctx, network, contract)?;
+    if redact_addresses {
+        for address in history.iter_mut().filter_map(|entry| entry.address.as_mut()) {
+            *address = REDACTED_ADDRESS.to_string();
+        }
+    }
+
+    if json {
+        println!("{}", to_json(&history)?);
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.set_format(create_sui_style_format());
+    table.add_row(row![bF-> "Commit", bF-> "Date", bF-> "Address"]);
+    for entry in &history {
+        table.add_row(row![entry.commit, entry.date, entry.address.as_deref().unwrap_or("(removed)")]);
+    }
+    table.printstd();
+    Ok(())
+}
+
+/// Renders a JSON report through a Tera template. The whole report is
+/// available as `report`, and the keys of a top-level object directly too.
+fn render_report(template: &Path, input: &Path, outfile: Option<&Path>) -> Result<(), String> {
+    let template = fs::read_to_string(template)
+        .map_err(|e| format!("Failed to read template {}: {}", template.display(), e))?;
+    let content = fs::read_to_string(input)
+        .map_err(|e| format!("Failed to read {}: {}", input.display(), e))?;
+    let report: Value = serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("Failed to parse {}: {}", input.display(), e))?;
+
+    let mut context = tera::Context::new();
+    if let Value::Object(fields) = &report {
+        for (key, value) in fields {
+            context.insert(key, value);
+        }
+    }
+    context.insert("report", &report);
+
+    let output = tera::Tera::one_off(&template, &context, false).map_err(|e| {
+        // Tera's own message only names the template, the cause is in the sources
+        let mut message = e.to_string();
+        let mut source = std::error::Error::source(&e);
+        while let Some(cause) = source {
+            message.push_str(&format!(": {}", cause));
+            source = cause.source();
+        }
+        format!("Failed to render template: {}", message)
+    })?;
+
+    if let Some(path) = outfile {
+        write_output(path, output)?;
+    } else {
+        print!("{}", output);
+    }
+    Ok(())
+}
+
+/// Prints the health checks and returns the exit code summarizing them.
+fn health_deployments(ctx: &Context, json: bool) -> Result<i32, String> {
+    let report = build_health_report(ctx)?;
+
+    if json {
+        let output = to_json(&report)?;
+        println!("{}", output);
+    } else {
+        let mut table = Table::new();
+        table.set_format(create_sui_style_format());
+        table.add_row(row![bF-> "Check", bF-> "Status", bF-> "Details"]);
+        for check in &report.checks {
+            let status = if check.passed { cell!(Fg-> "Pass") } else { cell!(Fr-> "Fail") };
+            table.add_row(Row::new(vec![cell!(check.name), status, cell!(check.details)]));
+        }
+        table.printstd();
+
+        let failed = report.failed();
+        if failed == 0 {
+            println!("All checks passed");
+        } else {
+            println!("{} check(s) failed (exit code {})", failed, report.exit_code);
+        }
+    }
+
+    Ok(report.exit_code)
+}
+
+/// Collects the `--network-name-map` values, each either a mapping or a file
+/// of mappings, into the registry overrides. Later mappings win.
+fn load_network_names(values: &[String]) -> Result<HashMap<u64, String>, String> {
+    let mut overrides = HashMap::new();
+    for value in values {
+        if value.contains('=') {
+            let (chain_id, name) = registry::parse_override(value)?;
+            overrides.insert(chain_id, name);
+        } else {
+            overrides.extend(registry::load_overrides(Path::new(value))?);
+        }
+    }
+    Ok(overrides)
+}
+
+/// Prints the network and contract counts, or with `dirs_only` just the
+/// number of deployment directories.
+fn show_counts(ctx: &Context, json: bool, dirs_only: bool) -> Result<(), String> {
+    if dirs_only {
+        let count = count_deployments(ctx)?;
+        if json {
+            println!("{}", to_json(&json!({ "deployment_dirs": count }))?);
+        } else {
+            println!("Found {} deployment(s)", count);
+        }
+        return Ok(());
+    }
+
+    let report = build_list_report_at(ctx, ContractSelection::All, None)?;
+    let counts = report.counts();
+    if json {
+        println!("{}", to_json(&counts)?);
+    } else {
+        println!("Deployed to {} of {} configured network(s), {} contract(s) in total",
+            counts.deployed_networks, counts.configured_networks, counts.total_contracts);
+    }
+    Ok(())
+}
+
 fn main() {
-    let cli = Cli::parse();
-    
+    #[cfg(target_os = "linux")]
+    if std::env::var_os(CLIPBOARD_HOLDER_ENV).is_some() {
+        if let Err(e) = hold_clipboard() {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    let started = std::time::Instant::now();
+    let mut cli = Cli::parse();
+    JSON_INDENT.store(cli.json_indent, Ordering::Relaxed);
+
     match cli.command {
         None => {
             println!("No command provided. Use --help to see available commands.");
         }
         Some(cmd) => {
             // Handle version and update commands before project validation
-            let result = match cmd {
+            match cmd {
                 Commands::Version => {
                     println!("evm-deployment-info v{}", VERSION);
                     return;
@@ -916,23 +3175,23 @@ fn main() {
                         Ok(latest_version) => {
                             if !force && latest_version == VERSION {
                                 println!("You're already running the latest version ({})", VERSION);
-                                return ();
+                                return;
                             }
                             
                             println!("Current version: {}", VERSION);
                             println!("Latest version:  {}", latest_version);
                             
-                            if !force && latest_version < VERSION.to_string() {
+                            if !force && latest_version.as_str() < VERSION {
                                 println!("Warning: Latest version is older than current version");
                                 println!("Use --force to update anyway");
-                                return ();
+                                return;
                             }
 
                             if !check_install_permissions() {
                                 println!("Error: Insufficient permissions to perform update");
                                 println!("Please run with sudo:");
                                 println!("\n    sudo evm-deployment-info update\n");
-                                return ();
+                                return;
                             }
                             
                             println!("Installing update...");
@@ -949,41 +3208,252 @@ fn main() {
                                 Ok(status) => {
                                     if status.success() {
                                         println!("Successfully updated to version {}", latest_version);
-                                        return ();
+                                        return;
                                     } else {
                                         println!("Failed to update. Please try again or update manually");
-                                        return ();
+                                        return;
                                     }
                                 }
                                 Err(e) => {
                                     println!("Error during update: {}", e);
-                                    return ();
+                                    return;
                                 }
                             }
                         }
                         Err(e) => {
                             println!("Error checking for updates: {}", e);
-                            return ();
+                            return;
                         }
                     }
                 }
-                Commands::Count => count_deployments(&cli.project)
-                    .map(|count| println!("Found {} deployment(s)", count)),
-                Commands::List { aggregate, json, csv, md, outfile } => {
-                    list_deployments(&cli.project, aggregate, json, csv, md, outfile.as_deref())
+                _ => {}
+            }
+
+            // Deployments-only listings and counts, and rendering a report, don't need a hardhat config
+            let needs_config = !cli.no_validate
+                && !matches!(cmd, Commands::List { deployments_only: true, .. } | Commands::Count { dirs_only: true, .. } | Commands::Render { .. });
+            // --project may be an archived snapshot, which is read into memory
+            // instead of being extracted
+            let archive = if Archive::is_archive(&cli.project) {
+                match Archive::open(&cli.project) {
+                    Ok(archive) => Some(archive),
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            } else {
+                None
+            };
+            // --project may point at the config itself rather than its directory
+            let project_root = if archive.is_some() {
+                Ok((cli.project.clone(), None))
+            } else {
+                config::project_root(&cli.project)
+            };
+            let project_config = match project_root {
+                Ok((root, config_file)) => {
+                    cli.project = root;
+                    config_file
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            // For an archive, the config is read from the archive, including a
+            // --config path inside it (e.g. snapshot.tar.gz/hardhat.config.prod.ts)
+            let archived_config = match (&cli.config, &archive) {
+                (None, Some(_)) => Some(config::DEFAULT_CONFIG_FILE.to_string()),
+                (Some(path), Some(_)) => path.strip_prefix(&cli.project).ok()
+                    .map(|name| name.to_string_lossy().replace('\\', "/")),
+                _ => None,
+            };
+            let config_source = match (&cli.config, project_config, &archive, archived_config) {
+                (_, _, Some(archive), Some(name)) => match archive.read_file(&name) {
+                    Ok(Some(content)) => Ok(config::Source::Content { name, content }),
+                    Ok(None) => Err(format!("No {} found in archive {}", name, cli.project.display())),
+                    Err(e) => Err(e),
+                },
+                (Some(path), _, _, _) if path.as_os_str() == "-" => config::Source::stdin(&cli.config_stdin_name),
+                (Some(path), _, _, _) => Ok(config::Source::File(path.clone())),
+                (None, Some(path), _, _) => Ok(config::Source::File(path)),
+                (None, None, _, _) => Ok(config::Source::ProjectRoot),
+            };
+            let mut ctx = Context::new(cli.project.clone());
+            if let Some(archive) = archive {
+                ctx.source = Box::new(archive);
+            }
+            ctx.ignore_dirs = cli.ignore_dirs.clone();
+            ctx.timings = Timings::new(cli.timing);
+            if cli.error_mode == "fail-fast" {
+                ctx.errors = ErrorLog::new(ErrorMode::FailFast);
+            }
+            let validated = config_source
+                .and_then(|source| {
+                    ctx.config = source;
+                    ctx.network_names = load_network_names(&cli.network_name_map)?;
+                    if !cli.chain_globs.is_empty() {
+                        ctx.set_chain_globs(&cli.chain_globs)?;
+                    }
+                    if cli.require_config_file {
+                        require_config_networks(&ctx)
+                    } else if needs_config {
+                        validate_hardhat_project(&ctx)
+                    } else {
+                        Ok(())
+                    }
+                });
+            // Set by health, whose failed checks are reported through the exit code
+            let mut exit_code = 0;
+            let result = validated.and_then(|()| match cmd {
+                Commands::Count { json, dirs_only } => show_counts(&ctx, json, dirs_only),
+                Commands::List { aggregate, exclude_prefixes, group_threshold, group_sort, json, json_version, json_array, include_missing, csv, csv_bom, csv_delimiter, md, outfile, output_mode, dedupe, clipboard, no_header, abbrev_addresses, no_abbrev, max_width, mut contracts, contracts_file, networks, only_contracts_matching, primary_contract, collapse_identical, show_balances, balance_rpcs, show_tags, show_module, columns, sort_contracts, fail_on_malformed, normalize_network_names, max_age, alias_map, alias_json, explain, fields, by_contract, resolve_ens, rpc_url, git_ref, since_commit, aliases, strict_config, deployments_only, resolver_cmd, resolver_args } => {
+                    if let Some(path) = &contracts_file {
+                        contracts.extend(load_contract_names(path)?);
+                    }
+                    // JSON keeps the raw keys unless the aliases were asked for there too
+                    let alias_map = alias_map.as_deref().filter(|_| !(json || json_array) || alias_json);
+                    let selection = ListSelection {
+                        contracts: &contracts,
+                        primary_contract: primary_contract.as_deref(),
+                        contract_pattern: only_contracts_matching.as_deref(),
+                        all_contracts: by_contract,
+                        networks: &networks,
+                        exclude_prefixes: &exclude_prefixes,
+                        git_ref: git_ref.as_deref(),
+                        since_commit: since_commit.as_deref(),
+                        aliases: aliases.as_deref(),
+                        alias_map,
+                        show_tags,
+                        sort_contracts,
+                        normalize_network_names,
+                        deployments_only,
+                        resolver_cmd: resolver_cmd.as_deref(),
+                        resolver_args: &resolver_args,
+                        strict_config,
+                    };
+                    list_deployments(&ctx, &selection, &ListRenderOptions {
+                        aggregate,
+                        group_threshold,
+                        group_sort: &group_sort,
+                        json,
+                        json_version,
+                        json_array,
+                        include_missing,
+                        csv,
+                        csv_bom,
+                        csv_delimiter,
+                        md,
+                        outfile: outfile.as_deref(),
+                        output_mode: &output_mode,
+                        dedupe,
+                        clipboard,
+                        no_header,
+                        abbrev: abbrev_addresses && !no_abbrev,
+                        max_width,
+                        columns: &columns,
+                        fields: &fields,
+                        collapse_identical,
+                        show_balances,
+                        balance_rpcs: &balance_rpcs,
+                        show_module,
+                        fail_on_malformed,
+                        max_age,
+                        explain,
+                        by_contract,
+                        resolve_ens,
+                        rpc_url: rpc_url.as_deref(),
+                        pretty_numbers: cli.pretty_numbers,
+                        redact_addresses: cli.redact_addresses,
+                        show_contracts: selection.shows_contracts(),
+                        missing_description: selection.missing_description(),
+                        show_tags,
+                        show_commits: since_commit.is_some(),
+                        normalize_network_names,
+                    })
+                }
+                Commands::Networks { json } => list_networks(&ctx, json, cli.pretty_numbers),
+                Commands::Audit { compare_config: Some(other), json, outfile, .. } => {
+                    compare_configs(&ctx, &other, json, outfile.as_deref(), cli.pretty_numbers)
                 }
-                Commands::Audit { json, csv, outfile } => {
-                    audit_deployments(&cli.project, json, csv, outfile.as_deref())
+                Commands::Audit { json, csv, csv_bom, csv_delimiter, sarif, outfile, summary_only, forbid_chains_below, fail_on_malformed, max_age, expected_chains, check_selectors, page, page_size, compare_config: None } => {
+                    audit_deployments(&ctx, &AuditOptions {
+                        json,
+                        csv,
+                        csv_bom,
+                        csv_delimiter,
+                        sarif,
+                        outfile: outfile.as_deref(),
+                        summary_only,
+                        forbid_chains_below,
+                        fail_on_malformed,
+                        max_age,
+                        expected_chains: expected_chains.as_deref(),
+                        check_selectors,
+                        pretty_numbers: cli.pretty_numbers,
+                        redact_addresses: cli.redact_addresses,
+                        page,
+                        page_size,
+                    })
                 }
-                Commands::Version | Commands::Update { .. } => Ok(()),
+                Commands::Verify { network, rpc_url, expected, explain_no_code, rpc_timeout, rpc_retries, verify_onchain_time, json } => {
+                    verify_deployments(&ctx, &VerifyOptions {
+                        network: &network,
+                        rpc_url: &rpc_url,
+                        expected: &expected,
+                        explain_no_code,
+                        rpc_timeout: Duration::from_secs(rpc_timeout),
+                        rpc_retries,
+                        verify_onchain_time,
+                        json,
+                        redact_addresses: cli.redact_addresses,
+                    })
+                }
+                Commands::DiffContract { contract, from, to, only_changes, json } => diff_contract(&ctx, &contract, &from, &to, only_changes, json, cli.redact_addresses),
                 Commands::Coverage { json, csv, outfile } => {
-                    coverage_deployments(&cli.project, json, csv, outfile.as_deref())
+                    coverage_deployments(&ctx, json, csv, outfile.as_deref())
                 }
-            };
+                Commands::Stale { json, csv, outfile } => {
+                    stale_deployments(&ctx, json, csv, outfile.as_deref())
+                }
+                Commands::Tree { json } => tree_deployments(&ctx, json),
+                Commands::Open { network, contract } => {
+                    open_deployment(&ctx, &network, contract.as_deref())
+                }
+                Commands::Qr { network, contract, outfile } => {
+                    qr_deployment(&ctx, &network, contract.as_deref(), outfile.as_deref())
+                }
+                Commands::Graph { network, outfile } => {
+                    graph_deployments(&ctx, network.as_deref(), outfile.as_deref(), cli.redact_addresses)
+                }
+                Commands::History { network, contract, json } => {
+                    show_contract_history(&ctx, &network, &contract, json, cli.redact_addresses)
+                }
+                Commands::Where { network, contract, json } => {
+                    where_contract(&ctx, &network, contract.as_deref(), json)
+                }
+                Commands::Render { template, input, outfile } => {
+                    render_report(&template, &input, outfile.as_deref())
+                }
+                Commands::Health { json } => health_deployments(&ctx, json).map(|code| exit_code = code),
+                Commands::Version | Commands::Update { .. } => Ok(()),
+            });
+            ctx.timings.report(started.elapsed());
+            let skipped = ctx.errors.collected();
+            if !skipped.is_empty() {
+                eprintln!("Warning: Skipped {} deployment(s) that couldn't be read:", skipped.len());
+                for error in &skipped {
+                    eprintln!("  {}", error);
+                }
+            }
             if let Err(e) = result {
                 eprintln!("Error: {}", e);
                 std::process::exit(1);
             }
+            if exit_code != 0 {
+                std::process::exit(exit_code);
+            }
         }
     }
 }
@@ -0,0 +1,75 @@
+use std::fs;
+use std::path::Path;
+
+/// Names of well-known chains, keyed by chain ID.
+const CHAINS: &[(u64, &str)] = &[
+    (1, "Ethereum"),
+    (11155111, "Ethereum Sepolia"),
+    (17000, "Ethereum Holesky"),
+    (560048, "Ethereum Hoodi"),
+    (10, "OP Mainnet"),
+    (11155420, "OP Sepolia"),
+    (8453, "Base"),
+    (84532, "Base Sepolia"),
+    (42161, "Arbitrum One"),
+    (42170, "Arbitrum Nova"),
+    (421614, "Arbitrum Sepolia"),
+    (137, "Polygon"),
+    (80002, "Polygon Amoy"),
+    (1101, "Polygon zkEVM"),
+    (56, "BNB Smart Chain"),
+    (97, "BNB Smart Chain Testnet"),
+    (43114, "Avalanche C-Chain"),
+    (43113, "Avalanche Fuji"),
+    (250, "Fantom"),
+    (100, "Gnosis"),
+    (59144, "Linea"),
+    (59141, "Linea Sepolia"),
+    (534352, "Scroll"),
+    (534351, "Scroll Sepolia"),
+    (324, "zkSync Era"),
+    (300, "zkSync Era Sepolia"),
+    (81457, "Blast"),
+    (168587773, "Blast Sepolia"),
+    (5000, "Mantle"),
+    (5003, "Mantle Sepolia"),
+    (42220, "Celo"),
+    (7777777, "Zora"),
+    (999999999, "Zora Sepolia"),
+    (31337, "Hardhat"),
+    (1337, "Localhost"),
+];
+
+/// Parses a `<chain id>=<name>` override of the bundled names, such as
+/// `8453=Base Prod`, e.g. for internal chains or chains a team calls
+/// something else.
+pub fn parse_override(value: &str) -> Result<(u64, String), String> {
+    let (chain_id, name) = value.split_once('=')
+        .ok_or_else(|| format!("Invalid network name override {}: expected <chain id>=<name>", value))?;
+    let chain_id = chain_id.trim().parse::<u64>()
+        .map_err(|_| format!("Invalid chain ID {} in network name override {}", chain_id.trim(), value))?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(format!("Invalid network name override {}: the name is empty", value));
+    }
+    Ok((chain_id, name.to_string()))
+}
+
+/// Reads overrides from a file with one `<chain id>=<name>` per line. Lines
+/// starting with `#` are comments.
+pub fn load_overrides(path: &Path) -> Result<Vec<(u64, String)>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read network name map {}: {}", path.display(), e))?;
+    content.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(parse_override)
+        .collect()
+}
+
+pub fn chain_name(chain_id: u64) -> Option<&'static str> {
+    CHAINS
+        .iter()
+        .find(|(id, _)| *id == chain_id)
+        .map(|(_, name)| *name)
+}
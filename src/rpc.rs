@@ -0,0 +1,147 @@
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Delay before the first retry of a failed request, doubled for each
+/// further retry
+const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Minimal blocking JSON-RPC client for talking to an EVM node.
+pub struct RpcClient {
+    url: String,
+    client: reqwest::blocking::Client,
+    timeout: Option<Duration>,
+    retries: u32,
+}
+
+impl RpcClient {
+    pub fn new(url: &str) -> Result<Self, String> {
+        let client = reqwest::blocking::Client::builder()
+            .user_agent("evm-deployment-info-cli")
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+        Ok(Self {
+            url: url.to_string(),
+            client,
+            timeout: None,
+            retries: 0,
+        })
+    }
+
+    /// Gives up on a request that takes longer than `timeout`, instead of
+    /// the HTTP client's default of 30 seconds.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Retries requests that fail to reach the node or get no JSON-RPC
+    /// response (timeouts, connection resets, rate limiting pages) up to
+    /// `retries` times, with exponential backoff. Errors returned by the node
+    /// itself aren't retried.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    pub fn request(&self, method: &str, params: Value) -> Result<Value, String> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params
+        });
+        let mut attempt = 0;
+        let response = loop {
+            match self.send(&body) {
+                Ok(response) => break response,
+                Err(_) if attempt < self.retries => {
+                    std::thread::sleep(RETRY_BACKOFF * 2u32.pow(attempt));
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        };
+
+        if let Some(error) = response.get("error") {
+            return Err(format!("RPC error: {}", error["message"].as_str().unwrap_or("unknown error")));
+        }
+
+        Ok(response["result"].clone())
+    }
+
+    fn send(&self, body: &Value) -> Result<Value, String> {
+        let mut request = self.client.post(&self.url).json(body);
+        if let Some(timeout) = self.timeout {
+            request = request.timeout(timeout);
+        }
+        request.send()
+            .map_err(|e| format!("RPC request failed: {}", e))?
+            .json()
+            .map_err(|e| format!("Failed to parse RPC response: {}", e))
+    }
+
+    pub fn eth_call(&self, to: &str, data: &str) -> Result<String, String> {
+        self.request("eth_call", json!([{ "to": to, "data": data }, "latest"]))?
+            .as_str()
+            .map(String::from)
+            .ok_or_else(|| "Invalid eth_call result".to_string())
+    }
+
+    /// Returns the native token balance of `address` in wei.
+    pub fn eth_get_balance(&self, address: &str) -> Result<u128, String> {
+        let balance = self.request("eth_getBalance", json!([address, "latest"]))?;
+        balance.as_str()
+            .and_then(|hex| u128::from_str_radix(hex.trim_start_matches("0x"), 16).ok())
+            .ok_or_else(|| "Invalid eth_getBalance result".to_string())
+    }
+
+    /// Returns the number of the block a transaction was mined in, or `None`
+    /// if the node doesn't know the transaction or it is still pending.
+    pub fn eth_get_transaction_block(&self, hash: &str) -> Result<Option<u64>, String> {
+        let transaction = self.request("eth_getTransactionByHash", json!([hash]))?;
+        if transaction.is_null() || transaction["blockNumber"].is_null() {
+            return Ok(None);
+        }
+        transaction["blockNumber"].as_str()
+            .and_then(|hex| u64::from_str_radix(hex.trim_start_matches("0x"), 16).ok())
+            .map(Some)
+            .ok_or_else(|| "Invalid eth_getTransactionByHash result".to_string())
+    }
+
+    /// Returns the timestamp of a block in seconds since the Unix epoch, or
+    /// `None` if the node doesn't have the block.
+    pub fn eth_get_block_timestamp(&self, number: u64) -> Result<Option<u64>, String> {
+        let block = self.request("eth_getBlockByNumber", json!([format!("0x{:x}", number), false]))?;
+        if block.is_null() {
+            return Ok(None);
+        }
+        block["timestamp"].as_str()
+            .and_then(|hex| u64::from_str_radix(hex.trim_start_matches("0x"), 16).ok())
+            .map(Some)
+            .ok_or_else(|| "Invalid eth_getBlockByNumber result".to_string())
+    }
+
+    /// Returns the number of transactions sent from `address`.
+    pub fn eth_get_transaction_count(&self, address: &str) -> Result<u64, String> {
+        let count = self.request("eth_getTransactionCount", json!([address, "latest"]))?;
+        count.as_str()
+            .and_then(|hex| u64::from_str_radix(hex.trim_start_matches("0x"), 16).ok())
+            .ok_or_else(|| "Invalid eth_getTransactionCount result".to_string())
+    }
+}
+
+pub fn decode_hex(data: &str) -> Option<Vec<u8>> {
+    let data = data.trim_start_matches("0x");
+    if !data.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..data.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&data[i..i + 2], 16).ok())
+        .collect()
+}
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,233 @@
+//! Where a project's files are read from: the working tree, a git ref, or an
+//! archived snapshot (`.tar.gz` or `.zip`) read into memory without
+//! extracting it to disk.
+
+use crate::git;
+use flate2::read::GzDecoder;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Reads project files by their path relative to the project root, with `/`
+/// separators (e.g. `deployments/chain-8453/deployed_addresses.json`). The
+/// root itself is the empty path.
+pub trait DeploymentSource {
+    /// Reads the file at `path`, or `None` if it doesn't exist.
+    fn read_file(&self, path: &str) -> Result<Option<String>, String>;
+
+    /// Lists the directory at `path` as (name, whether it's a directory),
+    /// sorted by name, or `None` if there is no such directory.
+    fn list_dir(&self, path: &str) -> Result<Option<Vec<(String, bool)>>, String>;
+
+    /// When the file at `path` was last modified, if known.
+    fn modified(&self, _path: &str) -> Option<SystemTime> {
+        None
+    }
+
+    /// Whether the files are the project on disk, which commands that run
+    /// git or other programs on the project need.
+    fn is_working_tree(&self) -> bool {
+        false
+    }
+}
+
+/// The project as it is on disk.
+pub struct WorkingTree {
+    pub root: PathBuf,
+}
+
+impl DeploymentSource for WorkingTree {
+    fn read_file(&self, path: &str) -> Result<Option<String>, String> {
+        let path = self.root.join(path);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let name = path.file_name().map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+        fs::read_to_string(&path)
+            .map(Some)
+            .map_err(|e| format!("Failed to read {}: {}", name, e))
+    }
+
+    fn list_dir(&self, path: &str) -> Result<Option<Vec<(String, bool)>>, String> {
+        let dir = self.root.join(path);
+        if !dir.is_dir() {
+            return Ok(None);
+        }
+        let mut entries = fs::read_dir(&dir)
+            .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| (entry.file_name().to_string_lossy().into_owned(), entry.path().is_dir()))
+            .collect::<Vec<_>>();
+        entries.sort();
+        Ok(Some(entries))
+    }
+
+    fn modified(&self, path: &str) -> Option<SystemTime> {
+        fs::metadata(self.root.join(path)).and_then(|m| m.modified()).ok()
+    }
+
+    fn is_working_tree(&self) -> bool {
+        true
+    }
+}
+
+/// The project as it was committed at a git ref.
+pub struct GitRef<'a> {
+    pub root: &'a Path,
+    pub git_ref: &'a str,
+}
+
+impl DeploymentSource for GitRef<'_> {
+    fn read_file(&self, path: &str) -> Result<Option<String>, String> {
+        git::show_file(self.root, self.git_ref, path)
+    }
+
+    fn list_dir(&self, path: &str) -> Result<Option<Vec<(String, bool)>>, String> {
+        git::list_tree(self.root, self.git_ref, path)
+    }
+}
+
+/// A project snapshot distributed as an archive. Only the files this tool
+/// reads (see [`is_project_file`]) are kept in memory, and a directory
+/// wrapping the whole project (as in `snapshot/deployments/...`) is stripped
+/// from the paths.
+pub struct Archive {
+    files: BTreeMap<String, ArchivedFile>,
+}
+
+struct ArchivedFile {
+    content: Vec<u8>,
+    modified: Option<SystemTime>,
+}
+
+impl Archive {
+    /// Whether `path` names an archive this module can read.
+    pub fn is_archive(path: &Path) -> bool {
+        let name = path.to_string_lossy().to_lowercase();
+        name.ends_with(".tar.gz") || name.ends_with(".tgz") || name.ends_with(".zip")
+    }
+
+    pub fn open(path: &Path) -> Result<Archive, String> {
+        let file = File::open(path).map_err(|e| format!("Failed to open archive {}: {}", path.display(), e))?;
+        let mut files = BTreeMap::new();
+
+        if path.to_string_lossy().to_lowercase().ends_with(".zip") {
+            let mut zip = zip::ZipArchive::new(file)
+                .map_err(|e| format!("Failed to read archive {}: {}", path.display(), e))?;
+            for i in 0..zip.len() {
+                let mut entry = zip.by_index(i)
+                    .map_err(|e| format!("Failed to read archive {}: {}", path.display(), e))?;
+                let name = entry.name()
+                    .map_err(|e| format!("Failed to read archive {}: {}", path.display(), e))?
+                    .replace('\\', "/");
+                if entry.is_file() && is_project_file(&name) {
+                    let modified = entry.last_modified().and_then(|time| {
+                        civil_time(time.year().into(), time.month().into(), time.day().into(), time.hour().into(), time.minute().into(), time.second().into())
+                    });
+                    let mut content = Vec::new();
+                    entry.read_to_end(&mut content)
+                        .map_err(|e| format!("Failed to read {} from archive {}: {}", name, path.display(), e))?;
+                    files.insert(name, ArchivedFile { content, modified });
+                }
+            }
+        } else {
+            let mut tar = tar::Archive::new(GzDecoder::new(file));
+            let entries = tar.entries().map_err(|e| format!("Failed to read archive {}: {}", path.display(), e))?;
+            for entry in entries {
+                let mut entry = entry.map_err(|e| format!("Failed to read archive {}: {}", path.display(), e))?;
+                let name = entry.path()
+                    .map_err(|e| format!("Failed to read archive {}: {}", path.display(), e))?
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                if entry.header().entry_type().is_file() && is_project_file(&name) {
+                    let modified = entry.header().mtime().ok().map(|mtime| SystemTime::UNIX_EPOCH + Duration::from_secs(mtime));
+                    let mut content = Vec::new();
+                    entry.read_to_end(&mut content)
+                        .map_err(|e| format!("Failed to read {} from archive {}: {}", name, path.display(), e))?;
+                    files.insert(name, ArchivedFile { content, modified });
+                }
+            }
+        }
+
+        Ok(Archive { files: strip_wrapping_dir(files) })
+    }
+}
+
+impl DeploymentSource for Archive {
+    fn read_file(&self, path: &str) -> Result<Option<String>, String> {
+        self.files.get(path.trim_start_matches("./"))
+            .map(|file| String::from_utf8(file.content.clone()).map_err(|e| format!("Failed to read {}: {}", path, e)))
+            .transpose()
+    }
+
+    /// Archives only record files, so directories are the ones containing a
+    /// kept file.
+    fn list_dir(&self, path: &str) -> Result<Option<Vec<(String, bool)>>, String> {
+        let prefix = match path.trim_start_matches("./").trim_end_matches('/') {
+            "" => String::new(),
+            path => format!("{}/", path),
+        };
+        let mut entries: Vec<(String, bool)> = self.files.keys()
+            .filter_map(|file| file.strip_prefix(&prefix))
+            .map(|rest| match rest.split_once('/') {
+                Some((dir, _)) => (dir.to_string(), true),
+                None => (rest.to_string(), false),
+            })
+            .collect();
+        if entries.is_empty() {
+            return Ok(None);
+        }
+        entries.sort();
+        entries.dedup();
+        Ok(Some(entries))
+    }
+
+    fn modified(&self, path: &str) -> Option<SystemTime> {
+        self.files.get(path.trim_start_matches("./"))?.modified
+    }
+}
+
+/// Whether an archive entry is worth keeping: a hardhat config, or a file the
+/// commands read such as `deployed_addresses.json`, Ignition's
+/// `journal.jsonl`, `deployments-meta.toml` or a Solidity source.
+fn is_project_file(name: &str) -> bool {
+    let file_name = name.rsplit('/').next().unwrap_or(name);
+    file_name.starts_with("hardhat.config.")
+        || [".json", ".jsonl", ".toml", ".sol"].iter().any(|extension| file_name.ends_with(extension))
+}
+
+/// Converts a zip entry's timestamp, which has no time zone, to a time
+/// taking it as UTC.
+fn civil_time(year: i64, month: i64, day: i64, hour: u64, minute: u64, second: u64) -> Option<SystemTime> {
+    // Days since 1970-01-01 of a proleptic Gregorian date
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = u64::try_from(era * 146097 + day_of_era - 719468).ok()?;
+    Some(SystemTime::UNIX_EPOCH + Duration::from_secs(days * 86400 + hour * 3600 + minute * 60 + second))
+}
+
+/// Strips the leading directory shared by every path, if there is one and the
+/// project isn't at the top of the archive already.
+fn strip_wrapping_dir<T>(files: BTreeMap<String, T>) -> BTreeMap<String, T> {
+    let files: BTreeMap<String, T> = files.into_iter()
+        .map(|(path, content)| (path.trim_start_matches("./").to_string(), content))
+        .collect();
+    let at_top = |path: &String| path.starts_with("deployments/") || path.starts_with("hardhat.config.");
+    if files.keys().any(at_top) {
+        return files;
+    }
+    let Some(wrapper) = files.keys().next().and_then(|path| path.split_once('/')).map(|(dir, _)| format!("{}/", dir)) else {
+        return files;
+    };
+    if !files.keys().all(|path| path.starts_with(&wrapper)) {
+        return files;
+    }
+    files.into_iter()
+        .map(|(path, content)| (path[wrapper.len()..].to_string(), content))
+        .collect()
+}
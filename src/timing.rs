@@ -0,0 +1,50 @@
+//! Opt-in breakdown of where a run spends its time, for diagnosing slow runs
+//! on large repos. Phases add up across calls and are printed to stderr so
+//! machine output on stdout stays clean.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The time spent in each phase of a run, recorded only when enabled.
+#[derive(Debug, Default)]
+pub struct Timings {
+    enabled: bool,
+    phases: Mutex<Vec<(&'static str, Duration)>>,
+}
+
+impl Timings {
+    pub fn new(enabled: bool) -> Timings {
+        Timings { enabled, phases: Mutex::new(Vec::new()) }
+    }
+
+    /// Runs `f`, adding the time it took to `phase` when timing is enabled.
+    pub fn time<T>(&self, phase: &'static str, f: impl FnOnce() -> T) -> T {
+        if !self.enabled {
+            return f();
+        }
+        let start = Instant::now();
+        let result = f();
+        let elapsed = start.elapsed();
+
+        let mut phases = self.phases.lock().unwrap_or_else(|e| e.into_inner());
+        match phases.iter_mut().find(|(name, _)| *name == phase) {
+            Some((_, total)) => *total += elapsed,
+            None => phases.push((phase, elapsed)),
+        }
+        result
+    }
+
+    /// Prints the time spent in each phase, in the order they first ran, and
+    /// the total time of the run.
+    pub fn report(&self, total: Duration) {
+        if !self.enabled {
+            return;
+        }
+        let phases = self.phases.lock().unwrap_or_else(|e| e.into_inner());
+        eprintln!("Timing:");
+        for (phase, elapsed) in phases.iter() {
+            eprintln!("  {:<20} {:>10.2?}", phase, elapsed);
+        }
+        eprintln!("  {:<20} {:>10.2?}", "total", total);
+    }
+}
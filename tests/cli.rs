@@ -0,0 +1,131 @@
+use std::fs;
+use std::process::Command;
+
+const CONFIG: &str = r#"
+const config: HardhatUserConfig = {
+  networks: {
+    hardhat: {},
+    base: { url: "https://mainnet.base.org", chainId: 8453 },
+    baseSepolia: { url: "https://sepolia.base.org", chainId: 84532 },
+  },
+};
+"#;
+
+#[test]
+fn list_json_defaults_to_the_latest_version() {
+    let project = tempfile::tempdir().unwrap();
+    fs::write(project.path().join("hardhat.config.ts"), CONFIG).unwrap();
+    let chain_dir = project.path().join("deployments").join("chain-8453");
+    fs::create_dir_all(&chain_dir).unwrap();
+    fs::write(chain_dir.join("deployed_addresses.json"), r#"{"TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"}"#).unwrap();
+
+    let list_json = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_evm-deployment-info"))
+            .arg("--project").arg(project.path())
+            .args(["list", "--json"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+
+    let latest = list_json(&[]);
+    assert_eq!(latest["version"], 2);
+    assert_eq!(latest["deployments"][0]["network"], "base");
+    assert_eq!(latest["deployments"][0]["contracts"][0]["address"], "0x5FbDB2315678afecb367f032d93F642f64180aa3");
+
+    let legacy = list_json(&["--json-version", "1"]);
+    assert_eq!(legacy, serde_json::json!({
+        "version": 1,
+        "deployments": { "base": "0x5FbDB2315678afecb367f032d93F642f64180aa3" },
+        "missing": ["baseSepolia"],
+    }));
+}
+
+#[test]
+fn redact_addresses_conflicts_with_show_balances_in_any_position() {
+    let project = tempfile::tempdir().unwrap();
+    fs::write(project.path().join("hardhat.config.ts"), CONFIG).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_evm-deployment-info"))
+        .arg("--project").arg(project.path())
+        .args(["list", "--show-balances", "--redact-addresses"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("'--show-balances' cannot be used with '--redact-addresses'"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_evm-deployment-info"))
+        .arg("--project").arg(project.path())
+        .args(["--redact-addresses", "list", "--show-balances"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--show-balances can't be used with --redact-addresses"));
+}
+
+#[test]
+fn csv_fields_containing_the_delimiter_are_quoted() {
+    let project = tempfile::tempdir().unwrap();
+    fs::write(project.path().join("hardhat.config.ts"), CONFIG).unwrap();
+    let chain_dir = project.path().join("deployments").join("chain-8453");
+    fs::create_dir_all(&chain_dir).unwrap();
+    fs::write(chain_dir.join("deployed_addresses.json"), r#"{"TokenModule#Token": "0x5FbDB2315678afecb367f032d93F642f64180aa3"}"#).unwrap();
+    fs::write(project.path().join("aliases.toml"), r#""TokenModule#Token" = "Token; \"Base\", v2""#).unwrap();
+
+    for delimiter in [",", ";"] {
+        let output = Command::new(env!("CARGO_BIN_EXE_evm-deployment-info"))
+            .arg("--project").arg(project.path())
+            .args(["list", "--csv", "--csv-delimiter", delimiter, "--contracts", "Token", "--alias-map"])
+            .arg(project.path().join("aliases.toml"))
+            .output()
+            .unwrap();
+
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let row = ["Base", "base", r#""Token; ""Base"", v2""#, "0x5FbDB2315678afecb367f032d93F642f64180aa3"].join(delimiter);
+        assert!(stdout.lines().any(|line| line == row), "{}", stdout);
+    }
+}
+
+#[test]
+fn audit_sarif_uris_are_relative_to_the_project() {
+    let project = tempfile::tempdir().unwrap();
+    fs::write(project.path().join("hardhat.config.ts"), CONFIG).unwrap();
+    let chain_dir = project.path().join("deployments").join("chain-1");
+    fs::create_dir_all(&chain_dir).unwrap();
+    fs::write(chain_dir.join("deployed_addresses.json"), r#"{"TokenModule#Token": " 0x5FbDB2315678afecb367f032d93F642f64180aa3"}"#).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_evm-deployment-info"))
+        .arg("--project").arg(project.path().canonicalize().unwrap())
+        .args(["audit", "--sarif"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let sarif: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let mut uris: Vec<&str> = sarif["runs"][0]["results"].as_array().unwrap().iter()
+        .flat_map(|result| result["locations"].as_array().unwrap())
+        .map(|location| location["physicalLocation"]["artifactLocation"]["uri"].as_str().unwrap())
+        .collect();
+    uris.sort();
+    uris.dedup();
+    assert_eq!(uris, ["deployments/chain-1/deployed_addresses.json", "hardhat.config.ts"]);
+}
+
+#[test]
+fn dedupe_requires_an_output_mode() {
+    let project = tempfile::tempdir().unwrap();
+    fs::write(project.path().join("hardhat.config.ts"), CONFIG).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_evm-deployment-info"))
+        .arg("--project").arg(project.path())
+        .args(["list", "--json-array", "--dedupe", "--outfile"])
+        .arg(project.path().join("records.json"))
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--output-mode <OUTPUT_MODE>"));
+}